@@ -0,0 +1,46 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+
+// Inputs from tests/parser.rs
+const NAMED: &[&str] = &[
+    "transparent",
+    "aliceblue",
+    "dodgerblue",
+    "yellowgreen",
+    "lime",
+];
+
+const HEX: &[&str] = &["#f90", "#ABCD", "#FF9900", "#ff00ff64", "BAD455"];
+
+const FUNCTIONAL: &[&str] = &[
+    "rgb(247,179,99)",
+    "rgb(0 255 127 / 75%)",
+    "rgb(46.27% 32.94% 80.39%)",
+    "hsl(180,0.5,0.27)",
+    "hsl(120deg 100% 50%)",
+    "hwb(480deg 0% 0% / 100%)",
+    "hsv(0 0% 50%)",
+    "oklch(0.86644, 0.29483, 142.49535)",
+    "color(display-p3 0.91749 0.20029 0.13856)",
+    "rgb(from #3366cc b g r)",
+    "rgb(calc(255 * 0.5) 0 0)",
+    "color-mix(in srgb, red 30%, blue)",
+];
+
+fn bench_inputs(c: &mut Criterion, group: &str, inputs: &[&str]) {
+    c.bench_function(group, |b| {
+        b.iter(|| {
+            for s in inputs {
+                let _ = black_box(csscolorparser::parse(black_box(s)));
+            }
+        })
+    });
+}
+
+fn parse(c: &mut Criterion) {
+    bench_inputs(c, "parse named", NAMED);
+    bench_inputs(c, "parse hex", HEX);
+    bench_inputs(c, "parse functional", FUNCTIONAL);
+}
+
+criterion_group!(benches, parse);
+criterion_main!(benches);
@@ -0,0 +1,164 @@
+// Chromatic adaptation of CIE XYZ between white points, the linear von Kries
+// transform in the cone response space of each method.
+
+use crate::colorspace::{inverse3, mul3, mul33, Mat3};
+
+/// White point, the CIE XYZ of the white with `Y` = 1
+///
+/// # Examples
+///
+/// ```
+/// use csscolorparser::WhitePoint;
+///
+/// let w = WhitePoint::from_chromaticity(0.3127, 0.3290);
+/// assert_eq!(w, WhitePoint::D65);
+/// assert_eq!(w.xyz()[1], 1.0);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WhitePoint([f32; 3]);
+
+impl WhitePoint {
+    /// CIE standard illuminant A, incandescent light.
+    pub const A: Self = Self::from_chromaticity(0.44757, 0.40745);
+    /// CIE standard illuminant C, average daylight.
+    pub const C: Self = Self::from_chromaticity(0.31006, 0.31616);
+    /// D50, horizon light, the white of `xyz-d50`, `lab()` and `lch()` in CSS.
+    pub const D50: Self = Self::from_chromaticity(0.3457, 0.3585);
+    /// D55, mid-morning daylight.
+    pub const D55: Self = Self::from_chromaticity(0.33242, 0.34743);
+    /// D65, noon daylight, the white of sRGB and `xyz-d65` in CSS.
+    pub const D65: Self = Self::from_chromaticity(0.3127, 0.3290);
+    /// D75, north sky daylight.
+    pub const D75: Self = Self::from_chromaticity(0.29902, 0.31485);
+    /// Equal energy illuminant E.
+    pub const E: Self = Self([1.0, 1.0, 1.0]);
+
+    /// White point with CIE XYZ `[x, y, z]`, scaled to `Y` = 1.
+    pub fn new(xyz: [f32; 3]) -> Self {
+        let [x, y, z] = xyz;
+        Self([x / y, 1.0, z / y])
+    }
+
+    /// White point with the CIE xy chromaticity `x`, `y`.
+    pub const fn from_chromaticity(x: f32, y: f32) -> Self {
+        Self([x / y, 1.0, (1.0 - x - y) / y])
+    }
+
+    /// Returns: `[x, y, z]`
+    pub fn xyz(&self) -> [f32; 3] {
+        self.0
+    }
+}
+
+/// Chromatic adaptation transform of CIE XYZ from one white point to
+/// another, see [`ChromaticAdaptation::adapt()`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum ChromaticAdaptation {
+    /// Bradford, used by CSS and ICC profiles.
+    #[default]
+    Bradford,
+    /// CAT02, of CIECAM02.
+    Cat02,
+    /// CAT16, of CAM16.
+    Cat16,
+    /// von Kries, with the Hunt-Pointer-Estevez cone responses.
+    VonKries,
+}
+
+impl ChromaticAdaptation {
+    /// Convert CIE XYZ relative to the white point `from` to the white point
+    /// `to`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use csscolorparser::{ChromaticAdaptation, WhitePoint};
+    ///
+    /// let d65 = WhitePoint::D65.xyz();
+    /// let [x, y, z] = ChromaticAdaptation::Cat16.adapt(d65, WhitePoint::D65, WhitePoint::A);
+    /// let [xa, ya, za] = WhitePoint::A.xyz();
+    ///
+    /// assert!((x - xa).abs() < 1e-5);
+    /// assert!((y - ya).abs() < 1e-5);
+    /// assert!((z - za).abs() < 1e-5);
+    /// ```
+    pub fn adapt(self, xyz: [f32; 3], from: WhitePoint, to: WhitePoint) -> [f32; 3] {
+        if from == to {
+            return xyz;
+        }
+        mul3(&self.matrix(from, to), xyz)
+    }
+
+    /// The matrix of [`adapt()`](Self::adapt), to multiply column vectors
+    /// of CIE XYZ.
+    pub fn matrix(self, from: WhitePoint, to: WhitePoint) -> [[f32; 3]; 3] {
+        let m = self.cone_response();
+        let [s0, s1, s2] = mul3(&m, from.0);
+        let [d0, d1, d2] = mul3(&m, to.0);
+        let scale = [
+            [d0 / s0, 0.0, 0.0],
+            [0.0, d1 / s1, 0.0],
+            [0.0, 0.0, d2 / s2],
+        ];
+        mul33(&inverse3(&m), &mul33(&scale, &m))
+    }
+
+    fn cone_response(self) -> Mat3 {
+        match self {
+            Self::Bradford => [
+                [0.8951, 0.2664, -0.1614],
+                [-0.7502, 1.7135, 0.0367],
+                [0.0389, -0.0685, 1.0296],
+            ],
+            Self::Cat02 => [
+                [0.7328, 0.4296, -0.1624],
+                [-0.7036, 1.6975, 0.0061],
+                [0.0030, 0.0136, 0.9834],
+            ],
+            Self::Cat16 => [
+                [0.401288, 0.650173, -0.051461],
+                [-0.250268, 1.204414, 0.045854],
+                [-0.002079, 0.048952, 0.953127],
+            ],
+            Self::VonKries => [
+                [0.40024, 0.70760, -0.08081],
+                [-0.22630, 1.16532, 0.04570],
+                [0.0, 0.0, 0.91822],
+            ],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::colorspace::{D50_TO_D65, D65_TO_D50};
+
+    #[test]
+    fn bradford_matches_css() {
+        let methods = [
+            (WhitePoint::D65, WhitePoint::D50, D65_TO_D50),
+            (WhitePoint::D50, WhitePoint::D65, D50_TO_D65),
+        ];
+        for (from, to, expected) in methods {
+            let m = ChromaticAdaptation::Bradford.matrix(from, to);
+            for i in 0..3 {
+                for j in 0..3 {
+                    assert!((m[i][j] - expected[i][j]).abs() < 1e-4, "{:?}", m);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn inverse() {
+        let m = ChromaticAdaptation::Cat02.cone_response();
+        let id = mul33(&m, &inverse3(&m));
+        for (i, row) in id.iter().enumerate() {
+            for (j, v) in row.iter().enumerate() {
+                let expected = if i == j { 1.0 } else { 0.0 };
+                assert!((v - expected).abs() < 1e-5, "{:?}", id);
+            }
+        }
+    }
+}
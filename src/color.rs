@@ -1,8 +1,10 @@
-use std::convert::TryFrom;
-#[cfg(feature = "lab")]
-use std::f32::consts::{PI, TAU};
-use std::fmt;
-use std::str::FromStr;
+use core::convert::TryFrom;
+use core::f32::consts::{PI, TAU};
+use core::fmt;
+use core::str::FromStr;
+
+#[cfg(feature = "alloc")]
+use alloc::{format, string::String};
 
 #[cfg(feature = "rust-rgb")]
 use rgb::{RGB, RGBA};
@@ -10,15 +12,18 @@ use rgb::{RGB, RGBA};
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
-#[cfg(feature = "lab")]
-use lab::{LCh, Lab};
-
-use crate::{parse, ParseColorError};
+use crate::colorspace::*;
+use crate::contrast;
+use crate::gamut::{self, Gamut, GamutMapping};
+#[cfg(not(any(feature = "std", test)))]
+use crate::math::FloatExt;
+#[cfg(feature = "alloc")]
+use crate::Dialect;
+use crate::{
+    parse, ChromaticAdaptation, ColorSpace, DeltaE, HueInterpolation, ParseColorError, Parser,
+    TextSize, WcagLevel, WhitePoint,
+};
 
-#[cfg(feature = "named-colors")]
-use crate::parser::NAMED_COLORS;
-
-#[cfg(feature = "lab")]
 const PI_3: f32 = PI * 3.0;
 
 #[derive(Debug, Clone, PartialEq, PartialOrd)]
@@ -41,6 +46,9 @@ impl Color {
     /// * `g`: Green value [0..1]
     /// * `b`: Blue value [0..1]
     /// * `a`: Alpha value [0..1]
+    ///
+    /// Red, green and blue outside the range [0..1] are extended sRGB, they
+    /// are kept by all conversions, see [`Color::is_in_gamut()`].
     pub const fn new(r: f32, g: f32, b: f32, a: f32) -> Self {
         Self { r, g, b, a }
     }
@@ -49,6 +57,7 @@ impl Color {
         [self.r, self.g, self.b, self.a]
     }
 
+    /// Components in the range [0..255], clamped if out of range.
     pub fn to_rgba8(&self) -> [u8; 4] {
         [
             (self.r * 255.0 + 0.5) as u8,
@@ -58,6 +67,7 @@ impl Color {
         ]
     }
 
+    /// Components in the range [0..65535], clamped if out of range.
     pub fn to_rgba16(&self) -> [u16; 4] {
         [
             (self.r * 65535.0 + 0.5) as u16,
@@ -67,6 +77,7 @@ impl Color {
         ]
     }
 
+    /// Clamp red, green, blue and alpha to the range [0..1].
     pub fn clamp(&self) -> Self {
         Self {
             r: self.r.clamp(0.0, 1.0),
@@ -76,6 +87,46 @@ impl Color {
         }
     }
 
+    /// Red, green and blue are in the range [0..1], allowing for the
+    /// rounding error of conversions.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use csscolorparser::Color;
+    ///
+    /// assert!(Color::from_oklcha(0.5, 0.1, 1.0, 1.0).is_in_gamut());
+    /// assert!(!Color::from_oklcha(0.8, 0.35, 0.5, 1.0).is_in_gamut());
+    /// assert!(!csscolorparser::parse("rgb(300 0 0)").unwrap().is_in_gamut());
+    /// ```
+    pub fn is_in_gamut(&self) -> bool {
+        Gamut::Srgb.contains(self)
+    }
+
+    /// Map the color into `gamut`, see [`GamutMapping`]. Colors in the gamut
+    /// are returned unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use csscolorparser::{Color, Gamut, GamutMapping};
+    ///
+    /// let c = Color::from_oklcha(0.8, 0.35, 0.5, 1.0);
+    /// let mapped = c.to_gamut(Gamut::Srgb, GamutMapping::Css);
+    ///
+    /// assert!(mapped.is_in_gamut());
+    /// assert!(Gamut::DisplayP3.contains(&c.to_gamut(Gamut::DisplayP3, GamutMapping::Css)));
+    /// ```
+    pub fn to_gamut(&self, gamut: Gamut, method: GamutMapping) -> Self {
+        gamut::map(self, gamut, method)
+    }
+
+    /// Like [`Color::to_rgba8()`], but mapped into the sRGB gamut with
+    /// `method` instead of clamping each component.
+    pub fn to_rgba8_mapped(&self, method: GamutMapping) -> [u8; 4] {
+        self.to_gamut(Gamut::Srgb, method).to_rgba8()
+    }
+
     #[deprecated = "Use [new](#method.new) instead."]
     /// Arguments:
     ///
@@ -160,13 +211,12 @@ impl Color {
     /// * `b`: Blue value [0..1]
     /// * `a`: Alpha value [0..1]
     pub fn from_linear_rgba(r: f32, g: f32, b: f32, a: f32) -> Self {
-        fn from_linear(x: f32) -> f32 {
-            if x >= 0.0031308 {
-                return 1.055 * x.powf(1.0 / 2.4) - 0.055;
-            }
-            12.92 * x
-        }
-        Self::new(from_linear(r), from_linear(g), from_linear(b), a)
+        Self::new(
+            srgb_from_linear(r),
+            srgb_from_linear(g),
+            srgb_from_linear(b),
+            a,
+        )
     }
 
     #[deprecated = "Use [from_linear_rgba8](#method.from_linear_rgba8) instead."]
@@ -227,8 +277,8 @@ impl Color {
     /// * `v`: Value [0..1]
     /// * `a`: Alpha [0..1]
     pub fn from_hsva(h: f32, s: f32, v: f32, a: f32) -> Self {
-        let (r, g, b) = hsv_to_rgb(normalize_angle(h), clamp0_1(s), clamp0_1(v));
-        Self::new(clamp0_1(r), clamp0_1(g), clamp0_1(b), clamp0_1(a))
+        let (r, g, b) = hsv_to_rgb(normalize_angle(h), s, v);
+        Self::new(r, g, b, a)
     }
 
     #[deprecated = "Use [from_hsla](#method.from_hsla) instead."]
@@ -248,8 +298,8 @@ impl Color {
     /// * `l`: Lightness [0..1]
     /// * `a`: Alpha [0..1]
     pub fn from_hsla(h: f32, s: f32, l: f32, a: f32) -> Self {
-        let (r, g, b) = hsl_to_rgb(normalize_angle(h), clamp0_1(s), clamp0_1(l));
-        Self::new(clamp0_1(r), clamp0_1(g), clamp0_1(b), clamp0_1(a))
+        let (r, g, b) = hsl_to_rgb(normalize_angle(h), s, l);
+        Self::new(r, g, b, a)
     }
 
     #[deprecated = "Use [from_hwba](#method.from_hwba) instead."]
@@ -269,8 +319,8 @@ impl Color {
     /// * `b`: Blackness [0..1]
     /// * `a`: Alpha [0..1]
     pub fn from_hwba(h: f32, w: f32, b: f32, a: f32) -> Self {
-        let (r, g, b) = hwb_to_rgb(normalize_angle(h), clamp0_1(w), clamp0_1(b));
-        Self::new(clamp0_1(r), clamp0_1(g), clamp0_1(b), a)
+        let (r, g, b) = hwb_to_rgb(normalize_angle(h), w, b);
+        Self::new(r, g, b, a)
     }
 
     #[deprecated = "Use [from_oklaba](#method.from_oklaba) instead."]
@@ -306,38 +356,145 @@ impl Color {
         Self::from_oklaba(l, c * h.cos(), c * h.sin(), alpha)
     }
 
-    #[cfg(feature = "lab")]
+    /// Arguments:
+    ///
+    /// * `x`: X value
+    /// * `y`: Y value
+    /// * `z`: Z value
+    /// * `alpha`: Alpha [0..1]
+    ///
+    /// CIE XYZ relative to the D65 white point.
+    pub fn from_xyza_d65(x: f32, y: f32, z: f32, alpha: f32) -> Self {
+        let [r, g, b] = mul3(&XYZ_D65_TO_SRGB, [x, y, z]);
+        Self::from_linear_rgba(r, g, b, alpha)
+    }
+
+    /// Arguments:
+    ///
+    /// * `x`: X value
+    /// * `y`: Y value
+    /// * `z`: Z value
+    /// * `alpha`: Alpha [0..1]
+    ///
+    /// CIE XYZ relative to the D50 white point.
+    pub fn from_xyza_d50(x: f32, y: f32, z: f32, alpha: f32) -> Self {
+        let [x, y, z] = mul3(&D50_TO_D65, [x, y, z]);
+        Self::from_xyza_d65(x, y, z, alpha)
+    }
+
+    /// Arguments:
+    ///
+    /// * `xyz`: X, Y and Z values
+    /// * `alpha`: Alpha [0..1]
+    /// * `white`: White point of `xyz`
+    /// * `method`: Chromatic adaptation to the D65 white point of sRGB
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use csscolorparser::{ChromaticAdaptation, Color, WhitePoint};
+    ///
+    /// let c = Color::from_xyza([1.0985, 1.0, 0.3558], 1.0, WhitePoint::A, ChromaticAdaptation::Cat16);
+    /// assert_eq!(c.to_rgba8(), [255, 255, 255, 255]);
+    /// ```
+    pub fn from_xyza(
+        xyz: [f32; 3],
+        alpha: f32,
+        white: WhitePoint,
+        method: ChromaticAdaptation,
+    ) -> Self {
+        let [x, y, z] = method.adapt(xyz, white, WhitePoint::D65);
+        Self::from_xyza_d65(x, y, z, alpha)
+    }
+
+    /// Arguments:
+    ///
+    /// * `r`: Red value [0..1]
+    /// * `g`: Green value [0..1]
+    /// * `b`: Blue value [0..1]
+    /// * `a`: Alpha value [0..1]
+    pub fn from_display_p3_rgba(r: f32, g: f32, b: f32, a: f32) -> Self {
+        let rgb = [srgb_to_linear(r), srgb_to_linear(g), srgb_to_linear(b)];
+        let [x, y, z] = mul3(&DISPLAY_P3_TO_XYZ_D65, rgb);
+        Self::from_xyza_d65(x, y, z, a)
+    }
+
+    /// Arguments:
+    ///
+    /// * `r`: Red value [0..1]
+    /// * `g`: Green value [0..1]
+    /// * `b`: Blue value [0..1]
+    /// * `a`: Alpha value [0..1]
+    pub fn from_a98_rgba(r: f32, g: f32, b: f32, a: f32) -> Self {
+        let rgb = [a98_to_linear(r), a98_to_linear(g), a98_to_linear(b)];
+        let [x, y, z] = mul3(&A98_RGB_TO_XYZ_D65, rgb);
+        Self::from_xyza_d65(x, y, z, a)
+    }
+
+    /// Arguments:
+    ///
+    /// * `r`: Red value [0..1]
+    /// * `g`: Green value [0..1]
+    /// * `b`: Blue value [0..1]
+    /// * `a`: Alpha value [0..1]
+    pub fn from_prophoto_rgba(r: f32, g: f32, b: f32, a: f32) -> Self {
+        let rgb = [
+            prophoto_to_linear(r),
+            prophoto_to_linear(g),
+            prophoto_to_linear(b),
+        ];
+        let [x, y, z] = mul3(&PROPHOTO_RGB_TO_XYZ_D50, rgb);
+        Self::from_xyza_d50(x, y, z, a)
+    }
+
+    /// Arguments:
+    ///
+    /// * `r`: Red value [0..1]
+    /// * `g`: Green value [0..1]
+    /// * `b`: Blue value [0..1]
+    /// * `a`: Alpha value [0..1]
+    pub fn from_rec2020_rgba(r: f32, g: f32, b: f32, a: f32) -> Self {
+        let rgb = [
+            rec2020_to_linear(r),
+            rec2020_to_linear(g),
+            rec2020_to_linear(b),
+        ];
+        let [x, y, z] = mul3(&REC2020_TO_XYZ_D65, rgb);
+        Self::from_xyza_d65(x, y, z, a)
+    }
+
     /// Arguments:
     ///
     /// * `l`: Lightness
     /// * `a`: Distance along the `a` axis
     /// * `b`: Distance along the `b` axis
     /// * `alpha`: Alpha [0..1]
+    ///
+    /// CIE Lab relative to the D50 white point, like `lab()` in CSS.
     pub fn from_laba(l: f32, a: f32, b: f32, alpha: f32) -> Self {
-        let [r, g, b] = Lab { l, a, b }.to_rgb_normalized();
-        Self::new(r, g, b, alpha)
+        let [x, y, z] = lab_to_xyz([l, a, b], D50_WHITE);
+        Self::from_xyza_d50(x, y, z, alpha)
     }
 
-    #[cfg(feature = "lab")]
     #[deprecated = "Use [from_laba](#method.from_laba) instead."]
     pub fn from_lab(l: f32, a: f32, b: f32, alpha: f32) -> Self {
         Self::from_laba(l, a, b, alpha)
     }
 
-    #[cfg(feature = "lab")]
     /// Returns: `[l, a, b, alpha]`
+    ///
+    /// CIE Lab relative to the D50 white point, like `lab()` in CSS.
     pub fn to_laba(&self) -> [f32; 4] {
-        let Lab { l, a, b } = Lab::from_rgb_normalized(&[self.r, self.g, self.b]);
+        let [x, y, z, _] = self.to_xyza_d50();
+        let [l, a, b] = xyz_to_lab([x, y, z], D50_WHITE);
         [l, a, b, self.a]
     }
 
-    #[cfg(feature = "lab")]
     #[deprecated = "Use [to_laba](#method.to_laba) instead."]
     pub fn to_lab(&self) -> [f32; 4] {
         self.to_laba()
     }
 
-    #[cfg(feature = "lab")]
     /// Blend this color with the other one, in the Lab color-space. `t` in the range [0..1].
     pub fn interpolate_lab(&self, other: &Color, t: f32) -> Self {
         let [l1, a1, b1, alpha1] = self.to_laba();
@@ -350,42 +507,44 @@ impl Color {
         )
     }
 
-    #[cfg(feature = "lab")]
     /// Arguments:
     ///
     /// * `l`: Lightness
     /// * `c`: Chroma
     /// * `h`: Hue angle in radians
     /// * `alpha`: Alpha [0..1]
+    ///
+    /// CIE LCH relative to the D50 white point, like `lch()` in CSS.
     pub fn from_lcha(l: f32, c: f32, h: f32, alpha: f32) -> Self {
-        let [r, g, b] = LCh { l, c, h }.to_lab().to_rgb_normalized();
-        Self::new(r, g, b, alpha)
+        Self::from_laba(l, c * h.cos(), c * h.sin(), alpha)
     }
 
-    #[cfg(feature = "lab")]
     #[deprecated = "Use [from_lcha](#method.from_lcha) instead."]
     pub fn from_lch(l: f32, c: f32, h: f32, alpha: f32) -> Self {
         Self::from_lcha(l, c, h, alpha)
     }
 
-    #[cfg(feature = "lab")]
     /// Returns: `[l, c, h, alpha]`
+    ///
+    /// CIE LCH relative to the D50 white point, like `lch()` in CSS.
     pub fn to_lcha(&self) -> [f32; 4] {
-        let LCh { l, c, h } = LCh::from_lab(Lab::from_rgb_normalized(&[self.r, self.g, self.b]));
-        [l, c, h, self.a]
+        let [l, a, b, alpha] = self.to_laba();
+        [l, a.hypot(b), b.atan2(a), alpha]
     }
 
-    #[cfg(feature = "lab")]
     #[deprecated = "Use [to_lcha](#method.to_lcha) instead."]
     pub fn to_lch(&self) -> [f32; 4] {
         self.to_lcha()
     }
 
-    #[cfg(feature = "lab")]
     /// Blend this color with the other one, in the LCH color-space. `t` in the range [0..1].
     pub fn interpolate_lch(&self, other: &Color, t: f32) -> Self {
         let [l1, c1, h1, alpha1] = self.to_lcha();
         let [l2, c2, h2, alpha2] = other.to_lcha();
+        let (h1, h2) = fix_powerless_hue(
+            (h1, ColorSpace::Lch.powerless_hue([l1, c1, h1, alpha1])),
+            (h2, ColorSpace::Lch.powerless_hue([l2, c2, h2, alpha2])),
+        );
         Self::from_lcha(
             l1 + t * (l2 - l1),
             c1 + t * (c2 - c1),
@@ -396,6 +555,9 @@ impl Color {
 
     /// Create color from CSS color string.
     ///
+    /// Use [`parse_html_legacy()`](crate::parse_html_legacy) for HTML
+    /// attributes such as `bgcolor`.
+    ///
     /// # Examples
     /// ```
     /// use csscolorparser::Color;
@@ -417,13 +579,14 @@ impl Color {
 
     #[cfg(feature = "named-colors")]
     pub fn name(&self) -> Option<&'static str> {
-        let rgb = &self.to_rgba8()[0..3];
-        for (&k, &v) in NAMED_COLORS.entries() {
-            if v == rgb {
-                return Some(k);
-            }
-        }
-        None
+        self.name_in(&Parser::new())
+    }
+
+    /// Returns the name of the color in the custom tables of `parser`, or in
+    /// the CSS named colors if `parser` accepts them. Alpha is ignored.
+    pub fn name_in<'a>(&self, parser: &Parser<'a>) -> Option<&'a str> {
+        let [r, g, b, _] = self.to_rgba8();
+        parser.color_name([r, g, b])
     }
 
     #[deprecated]
@@ -484,16 +647,10 @@ impl Color {
     ///
     /// * Red, green, blue and alpha in the range [0..1]
     pub fn to_linear_rgba(&self) -> [f32; 4] {
-        fn to_linear(x: f32) -> f32 {
-            if x >= 0.04045 {
-                return ((x + 0.055) / 1.055).powf(2.4);
-            }
-            x / 12.92
-        }
         [
-            to_linear(self.r),
-            to_linear(self.g),
-            to_linear(self.b),
+            srgb_to_linear(self.r),
+            srgb_to_linear(self.g),
+            srgb_to_linear(self.b),
             self.a,
         ]
     }
@@ -524,7 +681,100 @@ impl Color {
         [l, a, b, self.a]
     }
 
+    /// Returns: `[l, c, h, alpha]`
+    ///
+    /// * `h`: Hue angle in radians
+    pub fn to_oklcha(&self) -> [f32; 4] {
+        let [l, a, b, alpha] = self.to_oklaba();
+        [l, a.hypot(b), b.atan2(a), alpha]
+    }
+
+    /// Returns: `[x, y, z, alpha]`
+    ///
+    /// CIE XYZ relative to the D65 white point.
+    pub fn to_xyza_d65(&self) -> [f32; 4] {
+        let [r, g, b, _] = self.to_linear_rgba();
+        let [x, y, z] = mul3(&SRGB_TO_XYZ_D65, [r, g, b]);
+        [x, y, z, self.a]
+    }
+
+    /// Returns: `[x, y, z, alpha]`
+    ///
+    /// CIE XYZ relative to the D50 white point.
+    pub fn to_xyza_d50(&self) -> [f32; 4] {
+        let [x, y, z, _] = self.to_xyza_d65();
+        let [x, y, z] = mul3(&D65_TO_D50, [x, y, z]);
+        [x, y, z, self.a]
+    }
+
+    /// Returns: `[x, y, z, alpha]`
+    ///
+    /// CIE XYZ relative to the `white` point, adapted from the D65 white
+    /// point of sRGB with `method`.
+    pub fn to_xyza(&self, white: WhitePoint, method: ChromaticAdaptation) -> [f32; 4] {
+        let [x, y, z, _] = self.to_xyza_d65();
+        let [x, y, z] = method.adapt([x, y, z], WhitePoint::D65, white);
+        [x, y, z, self.a]
+    }
+
+    /// Returns: `[r, g, b, a]`
+    ///
+    /// * Red, green, blue and alpha in the Display P3 color space
+    pub fn to_display_p3_rgba(&self) -> [f32; 4] {
+        let [x, y, z, _] = self.to_xyza_d65();
+        let [r, g, b] = mul3(&XYZ_D65_TO_DISPLAY_P3, [x, y, z]);
+        [
+            srgb_from_linear(r),
+            srgb_from_linear(g),
+            srgb_from_linear(b),
+            self.a,
+        ]
+    }
+
+    /// Returns: `[r, g, b, a]`
+    ///
+    /// * Red, green, blue and alpha in the A98 RGB color space
+    pub fn to_a98_rgba(&self) -> [f32; 4] {
+        let [x, y, z, _] = self.to_xyza_d65();
+        let [r, g, b] = mul3(&XYZ_D65_TO_A98_RGB, [x, y, z]);
+        [
+            a98_from_linear(r),
+            a98_from_linear(g),
+            a98_from_linear(b),
+            self.a,
+        ]
+    }
+
+    /// Returns: `[r, g, b, a]`
+    ///
+    /// * Red, green, blue and alpha in the ProPhoto RGB color space
+    pub fn to_prophoto_rgba(&self) -> [f32; 4] {
+        let [x, y, z, _] = self.to_xyza_d50();
+        let [r, g, b] = mul3(&XYZ_D50_TO_PROPHOTO_RGB, [x, y, z]);
+        [
+            prophoto_from_linear(r),
+            prophoto_from_linear(g),
+            prophoto_from_linear(b),
+            self.a,
+        ]
+    }
+
+    /// Returns: `[r, g, b, a]`
+    ///
+    /// * Red, green, blue and alpha in the Rec. 2020 color space
+    pub fn to_rec2020_rgba(&self) -> [f32; 4] {
+        let [x, y, z, _] = self.to_xyza_d65();
+        let [r, g, b] = mul3(&XYZ_D65_TO_REC2020, [x, y, z]);
+        [
+            rec2020_from_linear(r),
+            rec2020_from_linear(g),
+            rec2020_from_linear(b),
+            self.a,
+        ]
+    }
+
     /// Get the RGB hexadecimal color string.
+    #[cfg(feature = "alloc")]
     pub fn to_hex_string(&self) -> String {
         let [r, g, b, a] = self.to_rgba8();
 
@@ -536,6 +786,7 @@ impl Color {
     }
 
     /// Get the CSS `rgb()` format string.
+    #[cfg(feature = "alloc")]
     pub fn to_rgb_string(&self) -> String {
         let [r, g, b, _] = self.to_rgba8();
 
@@ -546,6 +797,139 @@ impl Color {
         format!("rgb({},{},{})", r, g, b)
     }
 
+    /// Get the color in the notation of a platform, e.g. `#AARRGGBB` on
+    /// Android. See [`Dialect`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use csscolorparser::{Color, Dialect};
+    ///
+    /// let c = Color::from_rgba8(255, 0, 0, 128);
+    ///
+    /// assert_eq!(c.to_dialect_string(Dialect::Android), "#80FF0000");
+    /// assert_eq!(c.to_dialect_string(Dialect::Flutter), "Color(0x80FF0000)");
+    /// assert_eq!(c.to_dialect_string(Dialect::Qt), "QColor(255, 0, 0, 128)");
+    /// ```
+    #[cfg(feature = "alloc")]
+    pub fn to_dialect_string(&self, dialect: Dialect) -> String {
+        dialect.format(self)
+    }
+
+    /// This color composited over the `background` with source-over, in
+    /// sRGB like browsers do.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use csscolorparser::Color;
+    ///
+    /// let c = Color::new(0.0, 0.0, 0.0, 0.5).composite(&Color::new(1.0, 1.0, 1.0, 1.0));
+    /// assert_eq!(c.to_rgba8(), [128, 128, 128, 255]);
+    /// ```
+    pub fn composite(&self, background: &Color) -> Self {
+        let a = self.a + background.a * (1.0 - self.a);
+        if a == 0.0 {
+            return Self::new(0.0, 0.0, 0.0, 0.0);
+        }
+        let blend = |t: f32, b: f32| (t * self.a + b * background.a * (1.0 - self.a)) / a;
+        Self::new(
+            blend(self.r, background.r),
+            blend(self.g, background.g),
+            blend(self.b, background.b),
+            a,
+        )
+    }
+
+    /// Relative luminance of WCAG 2.x, 0 for black and 1 for white. Alpha
+    /// is ignored, use [`relative_luminance_over()`](Self::relative_luminance_over)
+    /// for a translucent color.
+    pub fn relative_luminance(&self) -> f32 {
+        let [r, g, b, _] = self.to_linear_rgba();
+        0.2126 * r + 0.7152 * g + 0.0722 * b
+    }
+
+    /// Relative luminance of WCAG 2.x of this color composited over the
+    /// `background`. A translucent background is composited over opaque
+    /// white first.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use csscolorparser::Color;
+    ///
+    /// let c = Color::new(0.0, 0.0, 0.0, 0.5);
+    /// let white = Color::new(1.0, 1.0, 1.0, 1.0);
+    /// let transparent = Color::new(0.0, 0.0, 0.0, 0.0);
+    ///
+    /// assert_eq!(c.relative_luminance(), 0.0);
+    /// assert_eq!(c.relative_luminance_over(&white), c.relative_luminance_over(&transparent));
+    /// ```
+    pub fn relative_luminance_over(&self, background: &Color) -> f32 {
+        self.composite(&contrast::opaque(background))
+            .relative_luminance()
+    }
+
+    /// WCAG 2.x contrast ratio of this color as text on the `background`,
+    /// from 1 to 21. A translucent background is composited over opaque
+    /// white, then the text over the background.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use csscolorparser::Color;
+    ///
+    /// let black = Color::new(0.0, 0.0, 0.0, 1.0);
+    /// let white = Color::new(1.0, 1.0, 1.0, 1.0);
+    ///
+    /// assert!((black.contrast_ratio(&white) - 21.0).abs() < 1e-4);
+    /// assert!((white.contrast_ratio(&black) - 21.0).abs() < 1e-4);
+    /// ```
+    pub fn contrast_ratio(&self, background: &Color) -> f32 {
+        contrast::contrast_ratio(self, background)
+    }
+
+    /// APCA lightness contrast (Lc) of this color as text on the
+    /// `background`, about 106 for black on white and -108 for white on
+    /// black, negative for light text on a dark background. A translucent
+    /// background is composited over opaque white, then the text over the
+    /// background, and colors out of the sRGB gamut are clamped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use csscolorparser::Color;
+    ///
+    /// let text = Color::from_rgba8(136, 136, 136, 255);
+    /// let background = Color::from_rgba8(255, 255, 255, 255);
+    ///
+    /// assert!((text.apca_contrast(&background) - 63.056).abs() < 1e-2);
+    /// assert!((background.apca_contrast(&text) + 68.541).abs() < 1e-2);
+    /// ```
+    pub fn apca_contrast(&self, background: &Color) -> f32 {
+        contrast::apca(self, background)
+    }
+
+    /// This color as text on the `background` meets the minimum contrast
+    /// ratio of the WCAG 2.x `level` for the text `size`. Translucent colors
+    /// are composited as in [`contrast_ratio()`](Self::contrast_ratio).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use csscolorparser::{Color, TextSize, WcagLevel};
+    ///
+    /// let text = Color::from_rgba8(118, 118, 118, 255);
+    /// let background = Color::new(1.0, 1.0, 1.0, 1.0);
+    ///
+    /// assert!(text.meets_wcag(&background, WcagLevel::Aa, TextSize::Normal));
+    /// assert!(!text.meets_wcag(&background, WcagLevel::Aaa, TextSize::Normal));
+    /// assert!(text.meets_wcag(&background, WcagLevel::Aaa, TextSize::Large));
+    /// ```
+    pub fn meets_wcag(&self, background: &Color, level: WcagLevel, size: TextSize) -> bool {
+        self.contrast_ratio(background) >= level.min_contrast_ratio(size)
+    }
+
     /// Blend this color with the other one, in the RGB color-space. `t` in the range [0..1].
     pub fn interpolate_rgb(&self, other: &Color, t: f32) -> Self {
         Self {
@@ -572,6 +956,10 @@ impl Color {
     pub fn interpolate_hsv(&self, other: &Color, t: f32) -> Self {
         let [h1, s1, v1, a1] = self.to_hsva();
         let [h2, s2, v2, a2] = other.to_hsva();
+        let (h1, h2) = fix_powerless_hue(
+            (h1, ColorSpace::Hsv.powerless_hue([h1, s1, v1, a1])),
+            (h2, ColorSpace::Hsv.powerless_hue([h2, s2, v2, a2])),
+        );
         Self::from_hsva(
             interp_angle(h1, h2, t),
             s1 + t * (s2 - s1),
@@ -580,6 +968,44 @@ impl Color {
         )
     }
 
+    /// Mix this color with the other one, like the CSS
+    /// [`color-mix()`](https://www.w3.org/TR/css-color-5/#color-mix) function.
+    ///
+    /// Arguments:
+    ///
+    /// * `other`: The other color
+    /// * `space`: Color space to mix in
+    /// * `hue`: Hue interpolation method, used only by polar color spaces
+    /// * `p1`: Proportion of this color [0..1]
+    /// * `p2`: Proportion of the other color [0..1]
+    ///
+    /// A missing proportion is `1` minus the other one, or `0.5` if both are missing.
+    /// If the proportions sum to less than `1`, the result's alpha is multiplied by the sum.
+    /// Returns `None` if a proportion is out of range or both are zero.
+    ///
+    /// # Examples
+    /// ```
+    /// use csscolorparser::{Color, ColorSpace, HueInterpolation};
+    ///
+    /// let a = Color::new(1.0, 0.0, 0.0, 1.0);
+    /// let b = Color::new(0.0, 0.0, 1.0, 1.0);
+    /// let c = a.mix(&b, ColorSpace::Srgb, HueInterpolation::Shorter, None, None).unwrap();
+    ///
+    /// assert_eq!(c.to_rgba8(), [128, 0, 128, 255]);
+    /// ```
+    pub fn mix(
+        &self,
+        other: &Color,
+        space: ColorSpace,
+        hue: HueInterpolation,
+        p1: Option<f32>,
+        p2: Option<f32>,
+    ) -> Option<Self> {
+        let c1 = (ColorSpace::Srgb, self.to_array());
+        let c2 = (ColorSpace::Srgb, other.to_array());
+        mix(space, hue, c1, c2, p1, p2).map(|c| space.to_color(c))
+    }
+
     /// Blend this color with the other one, in the [Oklab](https://bottosson.github.io/posts/oklab/) color-space. `t` in the range [0..1].
     pub fn interpolate_oklab(&self, other: &Color, t: f32) -> Self {
         let [l1, a1, b1, alpha1] = self.to_oklaba();
@@ -591,6 +1017,26 @@ impl Color {
             alpha1 + t * (alpha2 - alpha1),
         )
     }
+
+    /// The difference to the `other` color with the `method`, e.g.
+    /// [`DeltaE::Ciede2000`]
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use csscolorparser::{Color, DeltaE};
+    ///
+    /// let a = Color::from_rgba8(255, 0, 0, 255);
+    /// let b = Color::from_rgba8(250, 5, 0, 255);
+    /// let c = Color::from_rgba8(0, 0, 255, 255);
+    ///
+    /// assert!(a.distance(&b, DeltaE::Ciede2000) < 2.0);
+    /// assert!(a.distance(&c, DeltaE::Ciede2000) > 50.0);
+    /// assert_eq!(a.distance(&a, DeltaE::Ok), 0.0);
+    /// ```
+    pub fn distance(&self, other: &Color, method: DeltaE) -> f32 {
+        method.between(self, other)
+    }
 }
 
 impl Default for Color {
@@ -888,16 +1334,20 @@ fn interp_angle(a0: f32, a1: f32, t: f32) -> f32 {
     (a0 + t * delta + 360.0) % 360.0
 }
 
-#[cfg(feature = "lab")]
 #[inline]
 fn interp_angle_rad(a0: f32, a1: f32, t: f32) -> f32 {
     let delta = (((a1 - a0) % TAU) + PI_3) % TAU - PI;
     (a0 + t * delta + TAU) % TAU
 }
 
-#[inline]
-fn clamp0_1(t: f32) -> f32 {
-    t.clamp(0.0, 1.0)
+// Substitute a powerless hue with the other color's hue, so interpolating
+// from or to an achromatic color doesn't sweep through unrelated hues.
+fn fix_powerless_hue((h1, powerless1): (f32, bool), (h2, powerless2): (f32, bool)) -> (f32, f32) {
+    match (powerless1, powerless2) {
+        (true, false) => (h2, h2),
+        (false, true) => (h1, h1),
+        _ => (h1, h2),
+    }
 }
 
 #[inline]
@@ -0,0 +1,582 @@
+// Conversion matrices and transfer functions for the predefined color spaces.
+// https://www.w3.org/TR/css-color-4/#color-conversion-code
+
+#![allow(clippy::excessive_precision)]
+
+#[cfg(not(any(feature = "std", test)))]
+use crate::math::FloatExt;
+use crate::Color;
+
+/// Color spaces that colors can be mixed in, see [`Color::mix()`](struct.Color.html#method.mix).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ColorSpace {
+    Srgb,
+    SrgbLinear,
+    DisplayP3,
+    A98Rgb,
+    ProphotoRgb,
+    Rec2020,
+    Lab,
+    Lch,
+    Oklab,
+    Oklch,
+    XyzD50,
+    XyzD65,
+    Hsl,
+    Hwb,
+    /// Not in CSS standard.
+    Hsv,
+}
+
+// Analogous components, used to carry missing components over when
+// converting between color spaces.
+// https://www.w3.org/TR/css-color-4/#interpolation-missing
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Category {
+    Red,
+    Green,
+    Blue,
+    Lightness,
+    Colorfulness,
+    Hue,
+    OpponentA,
+    OpponentB,
+}
+
+impl ColorSpace {
+    // Index of the hue component, for polar color spaces.
+    pub(crate) fn hue_index(self) -> Option<usize> {
+        match self {
+            Self::Hsl | Self::Hwb | Self::Hsv => Some(0),
+            Self::Lch => Some(2),
+            Self::Oklch => Some(2),
+            _ => None,
+        }
+    }
+
+    // Components of the color in this color space, hue in degrees.
+    pub(crate) fn components(self, c: &Color) -> [f32; 4] {
+        match self {
+            Self::Srgb => c.to_array(),
+            Self::SrgbLinear => c.to_linear_rgba(),
+            Self::DisplayP3 => c.to_display_p3_rgba(),
+            Self::A98Rgb => c.to_a98_rgba(),
+            Self::ProphotoRgb => c.to_prophoto_rgba(),
+            Self::Rec2020 => c.to_rec2020_rgba(),
+            Self::Lab => c.to_laba(),
+            Self::Lch => {
+                let [l, c, h, alpha] = c.to_lcha();
+                [l, c, h.to_degrees().rem_euclid(360.0), alpha]
+            }
+            Self::Oklab => c.to_oklaba(),
+            Self::Oklch => {
+                let [l, c, h, alpha] = c.to_oklcha();
+                [l, c, h.to_degrees().rem_euclid(360.0), alpha]
+            }
+            Self::XyzD50 => c.to_xyza_d50(),
+            Self::XyzD65 => c.to_xyza_d65(),
+            Self::Hsl => c.to_hsla(),
+            Self::Hwb => c.to_hwba(),
+            Self::Hsv => c.to_hsva(),
+        }
+    }
+
+    // Inverse of `components()`. Missing components (NaN) are zero.
+    pub(crate) fn to_color(self, values: [f32; 4]) -> Color {
+        let [a, b, c, alpha] = values.map(|t| if t.is_nan() { 0.0 } else { t });
+        match self {
+            Self::Srgb => Color::new(a, b, c, alpha),
+            Self::SrgbLinear => Color::from_linear_rgba(a, b, c, alpha),
+            Self::DisplayP3 => Color::from_display_p3_rgba(a, b, c, alpha),
+            Self::A98Rgb => Color::from_a98_rgba(a, b, c, alpha),
+            Self::ProphotoRgb => Color::from_prophoto_rgba(a, b, c, alpha),
+            Self::Rec2020 => Color::from_rec2020_rgba(a, b, c, alpha),
+            Self::Lab => Color::from_laba(a, b, c, alpha),
+            Self::Lch => Color::from_lcha(a, b, c.to_radians(), alpha),
+            Self::Oklab => Color::from_oklaba(a, b, c, alpha),
+            Self::Oklch => Color::from_oklcha(a, b, c.to_radians(), alpha),
+            Self::XyzD50 => Color::from_xyza_d50(a, b, c, alpha),
+            Self::XyzD65 => Color::from_xyza_d65(a, b, c, alpha),
+            Self::Hsl => Color::from_hsla(a, b, c, alpha),
+            Self::Hwb => Color::from_hwba(a, b, c, alpha),
+            Self::Hsv => Color::from_hsva(a, b, c, alpha),
+        }
+    }
+
+    fn categories(self) -> [Option<Category>; 3] {
+        use Category::*;
+        match self {
+            Self::Srgb
+            | Self::SrgbLinear
+            | Self::DisplayP3
+            | Self::A98Rgb
+            | Self::ProphotoRgb
+            | Self::Rec2020
+            | Self::XyzD50
+            | Self::XyzD65 => [Some(Red), Some(Green), Some(Blue)],
+            Self::Lab => [Some(Lightness), Some(OpponentA), Some(OpponentB)],
+            Self::Lch => [Some(Lightness), Some(Colorfulness), Some(Hue)],
+            Self::Oklab => [Some(Lightness), Some(OpponentA), Some(OpponentB)],
+            Self::Oklch => [Some(Lightness), Some(Colorfulness), Some(Hue)],
+            Self::Hsl => [Some(Hue), Some(Colorfulness), Some(Lightness)],
+            Self::Hwb => [Some(Hue), None, None],
+            Self::Hsv => [Some(Hue), Some(Colorfulness), None],
+        }
+    }
+
+    // Whether the hue of an achromatic color, which carries no information.
+    // The thresholds absorb the rounding noise of f32 conversions.
+    pub(crate) fn powerless_hue(self, [_, b, c, _]: [f32; 4]) -> bool {
+        match self {
+            Self::Hsl | Self::Hsv => b.abs() < 1e-4,
+            Self::Hwb => b + c >= 1.0 - 1e-4,
+            Self::Lch => b.abs() < 0.05,
+            Self::Oklch => b.abs() < 2e-4,
+            _ => false,
+        }
+    }
+
+    // Convert components of a color in the `from` color space into this
+    // color space. Missing components are NaN; they are carried over to
+    // analogous components, and a powerless hue becomes missing.
+    pub(crate) fn convert(self, from: ColorSpace, values: [f32; 4]) -> [f32; 4] {
+        if from == self {
+            return values;
+        }
+
+        let color = from.to_color(values);
+        let mut out = self.components(&color);
+
+        if let Some(i) = self.hue_index() {
+            if self.powerless_hue(out) {
+                out[i] = f32::NAN;
+            }
+        }
+
+        let categories = self.categories();
+        for (i, category) in from.categories().iter().enumerate() {
+            if !values[i].is_nan() || category.is_none() {
+                continue;
+            }
+            if let Some(j) = categories.iter().position(|c| c == category) {
+                out[j] = f32::NAN;
+            }
+        }
+
+        if values[3].is_nan() {
+            out[3] = f32::NAN;
+        }
+
+        out
+    }
+}
+
+// Mix two colors given as components in their own color space, see
+// `Color::mix()`. Missing components (NaN) take the other color's value,
+// if both are missing the result is missing too.
+pub(crate) fn mix(
+    space: ColorSpace,
+    hue: HueInterpolation,
+    (space1, values1): (ColorSpace, [f32; 4]),
+    (space2, values2): (ColorSpace, [f32; 4]),
+    p1: Option<f32>,
+    p2: Option<f32>,
+) -> Option<[f32; 4]> {
+    let (p1, p2) = match (p1, p2) {
+        (None, None) => (0.5, 0.5),
+        (Some(p1), None) => (p1, 1.0 - p1),
+        (None, Some(p2)) => (1.0 - p2, p2),
+        (Some(p1), Some(p2)) => (p1, p2),
+    };
+
+    if !(0.0..=1.0).contains(&p1) || !(0.0..=1.0).contains(&p2) {
+        return None;
+    }
+
+    let sum = p1 + p2;
+    if sum <= 0.0 {
+        return None;
+    }
+    let (p1, p2) = (p1 / sum, p2 / sum);
+    let alpha_mult = sum.min(1.0);
+
+    let mut c1 = space.convert(space1, values1);
+    let mut c2 = space.convert(space2, values2);
+
+    for i in 0..4 {
+        if c1[i].is_nan() {
+            c1[i] = c2[i];
+        } else if c2[i].is_nan() {
+            c2[i] = c1[i];
+        }
+    }
+
+    let hue_index = space.hue_index();
+
+    if let Some(i) = hue_index {
+        let (h1, h2) = hue.fixup(c1[i], c2[i]);
+        c1[i] = h1;
+        c2[i] = h2;
+    }
+
+    // Interpolate with premultiplied alpha
+    let alpha = c1[3] * p1 + c2[3] * p2;
+    let (a1, a2) = if alpha.is_nan() {
+        (1.0, 1.0)
+    } else {
+        (c1[3], c2[3])
+    };
+    let mut c = [0.0; 4];
+
+    for (i, t) in c.iter_mut().take(3).enumerate() {
+        *t = if Some(i) == hue_index || alpha == 0.0 || alpha.is_nan() {
+            c1[i] * p1 + c2[i] * p2
+        } else {
+            (c1[i] * a1 * p1 + c2[i] * a2 * p2) / alpha
+        };
+    }
+    c[3] = alpha * alpha_mult;
+
+    Some(c)
+}
+
+/// Hue interpolation method for polar color spaces.
+///
+/// <https://www.w3.org/TR/css-color-4/#hue-interpolation>
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum HueInterpolation {
+    #[default]
+    Shorter,
+    Longer,
+    Increasing,
+    Decreasing,
+}
+
+impl HueInterpolation {
+    // Adjust the hues (in degrees) so that linear interpolation between them
+    // follows this method.
+    pub(crate) fn fixup(self, h1: f32, h2: f32) -> (f32, f32) {
+        let h1 = h1.rem_euclid(360.0);
+        let h2 = h2.rem_euclid(360.0);
+        let d = h2 - h1;
+        match self {
+            Self::Shorter if d > 180.0 => (h1 + 360.0, h2),
+            Self::Shorter if d < -180.0 => (h1, h2 + 360.0),
+            Self::Longer if d > 0.0 && d < 180.0 => (h1 + 360.0, h2),
+            Self::Longer if d > -180.0 && d <= 0.0 => (h1, h2 + 360.0),
+            Self::Increasing if d < 0.0 => (h1, h2 + 360.0),
+            Self::Decreasing if d > 0.0 => (h1 + 360.0, h2),
+            _ => (h1, h2),
+        }
+    }
+}
+
+pub(crate) type Mat3 = [[f32; 3]; 3];
+
+pub(crate) const SRGB_TO_XYZ_D65: Mat3 = [
+    [0.4123907993, 0.3575843394, 0.1804807884],
+    [0.2126390059, 0.7151686788, 0.0721923154],
+    [0.0193308187, 0.1191947798, 0.9505321522],
+];
+
+pub(crate) const XYZ_D65_TO_SRGB: Mat3 = [
+    [3.2409699419, -1.5373831776, -0.4986107603],
+    [-0.9692436363, 1.8759675015, 0.0415550574],
+    [0.0556300797, -0.2039769589, 1.0569715142],
+];
+
+pub(crate) const DISPLAY_P3_TO_XYZ_D65: Mat3 = [
+    [0.4865709486, 0.2656676932, 0.1982172852],
+    [0.2289745641, 0.6917385218, 0.0792869141],
+    [0.0000000000, 0.0451133819, 1.0439443689],
+];
+
+pub(crate) const XYZ_D65_TO_DISPLAY_P3: Mat3 = [
+    [2.4934969119, -0.9313836179, -0.4027107845],
+    [-0.8294889696, 1.7626640603, 0.0236246858],
+    [0.0358458302, -0.0761723893, 0.9568845240],
+];
+
+pub(crate) const A98_RGB_TO_XYZ_D65: Mat3 = [
+    [0.5766690429, 0.1855582379, 0.1882286462],
+    [0.2973449753, 0.6273635663, 0.0752914585],
+    [0.0270313614, 0.0706888525, 0.9913375368],
+];
+
+pub(crate) const XYZ_D65_TO_A98_RGB: Mat3 = [
+    [2.0415879038, -0.5650069743, -0.3447313508],
+    [-0.9692436363, 1.8759675015, 0.0415550574],
+    [0.0134442806, -0.1183623922, 1.0151749944],
+];
+
+pub(crate) const REC2020_TO_XYZ_D65: Mat3 = [
+    [0.6369580483, 0.1446169036, 0.1688809752],
+    [0.2627002120, 0.6779980715, 0.0593017165],
+    [0.0000000000, 0.0280726930, 1.0609850577],
+];
+
+pub(crate) const XYZ_D65_TO_REC2020: Mat3 = [
+    [1.7166511880, -0.3556707838, -0.2533662814],
+    [-0.6666843518, 1.6164812366, 0.0157685458],
+    [0.0176398574, -0.0427706133, 0.9421031212],
+];
+
+// ProPhoto RGB uses the D50 white point.
+pub(crate) const PROPHOTO_RGB_TO_XYZ_D50: Mat3 = [
+    [0.7977666449, 0.1351812974, 0.0313477341],
+    [0.2880748288, 0.7118352342, 0.0000899369],
+    [0.0000000000, 0.0000000000, 0.8251046025],
+];
+
+pub(crate) const XYZ_D50_TO_PROPHOTO_RGB: Mat3 = [
+    [1.3457868816, -0.2555720874, -0.0511018650],
+    [-0.5446307051, 1.5082477428, 0.0205274474],
+    [0.0000000000, 0.0000000000, 1.2119675456],
+];
+
+// Bradford chromatic adaptation
+pub(crate) const D65_TO_D50: Mat3 = [
+    [1.0479297925, 0.0229468706, -0.0501922663],
+    [0.0296278088, 0.9904344268, -0.0170737991],
+    [-0.0092430406, 0.0150551915, 0.7518742814],
+];
+
+pub(crate) const D50_TO_D65: Mat3 = [
+    [0.9554734215, -0.0230984549, 0.0632592432],
+    [-0.0283697093, 1.0099953981, 0.0210414412],
+    [0.0123140149, -0.0205076493, 1.3303659262],
+];
+
+// D65 and D50 white points, from their chromaticities as in CSS
+#[cfg(feature = "x11")]
+pub(crate) const D65_WHITE: [f32; 3] = [0.3127 / 0.3290, 1.0, (1.0 - 0.3127 - 0.3290) / 0.3290];
+pub(crate) const D50_WHITE: [f32; 3] = [0.3457 / 0.3585, 1.0, (1.0 - 0.3457 - 0.3585) / 0.3585];
+
+const KAPPA: f32 = 24389.0 / 27.0;
+const EPSILON: f32 = 216.0 / 24389.0;
+
+// CIE Lab to XYZ relative to `white`.
+pub(crate) fn lab_to_xyz([l, a, b]: [f32; 3], white: [f32; 3]) -> [f32; 3] {
+    let fy = (l + 16.0) / 116.0;
+    let fx = fy + a / 500.0;
+    let fz = fy - b / 200.0;
+    let f_inv = |t: f32| {
+        let t3 = t * t * t;
+        if t3 > EPSILON {
+            t3
+        } else {
+            (116.0 * t - 16.0) / KAPPA
+        }
+    };
+    [
+        f_inv(fx) * white[0],
+        lightness_to_y(l) * white[1],
+        f_inv(fz) * white[2],
+    ]
+}
+
+// XYZ relative to `white` to CIE Lab.
+pub(crate) fn xyz_to_lab(xyz: [f32; 3], white: [f32; 3]) -> [f32; 3] {
+    let f = |t: f32| {
+        if t > EPSILON {
+            t.cbrt()
+        } else {
+            (KAPPA * t + 16.0) / 116.0
+        }
+    };
+    let [fx, fy, fz] = [0, 1, 2].map(|i| f(xyz[i] / white[i]));
+    [116.0 * fy - 16.0, 500.0 * (fx - fy), 200.0 * (fy - fz)]
+}
+
+// Relative luminance of CIE lightness.
+pub(crate) fn lightness_to_y(l: f32) -> f32 {
+    if l > KAPPA * EPSILON {
+        let t = (l + 16.0) / 116.0;
+        t * t * t
+    } else {
+        l / KAPPA
+    }
+}
+
+#[inline]
+pub(crate) fn mul3(m: &Mat3, [a, b, c]: [f32; 3]) -> [f32; 3] {
+    [
+        m[0][0] * a + m[0][1] * b + m[0][2] * c,
+        m[1][0] * a + m[1][1] * b + m[1][2] * c,
+        m[2][0] * a + m[2][1] * b + m[2][2] * c,
+    ]
+}
+
+pub(crate) fn mul33(a: &Mat3, b: &Mat3) -> Mat3 {
+    let mut m = [[0.0; 3]; 3];
+    for (i, row) in m.iter_mut().enumerate() {
+        for (j, v) in row.iter_mut().enumerate() {
+            *v = (0..3).map(|k| a[i][k] * b[k][j]).sum();
+        }
+    }
+    m
+}
+
+// The inverse by the adjugate, `m` must not be singular.
+pub(crate) fn inverse3(m: &Mat3) -> Mat3 {
+    let cofactor = |i: usize, j: usize| {
+        let (i1, i2) = ((i + 1) % 3, (i + 2) % 3);
+        let (j1, j2) = ((j + 1) % 3, (j + 2) % 3);
+        m[i1][j1] * m[i2][j2] - m[i1][j2] * m[i2][j1]
+    };
+    let det: f32 = (0..3).map(|j| m[0][j] * cofactor(0, j)).sum();
+    let mut inv = [[0.0; 3]; 3];
+    for (i, row) in inv.iter_mut().enumerate() {
+        for (j, v) in row.iter_mut().enumerate() {
+            *v = cofactor(j, i) / det;
+        }
+    }
+    inv
+}
+
+// sRGB and Display P3 share the same transfer function. Like the others it
+// is extended to negative values by symmetry.
+
+pub(crate) fn srgb_to_linear(x: f32) -> f32 {
+    if x.abs() > 0.04045 {
+        return x.signum() * ((x.abs() + 0.055) / 1.055).powf(2.4);
+    }
+    x / 12.92
+}
+
+pub(crate) fn srgb_from_linear(x: f32) -> f32 {
+    if x.abs() > 0.0031308 {
+        return x.signum() * (1.055 * x.abs().powf(1.0 / 2.4) - 0.055);
+    }
+    12.92 * x
+}
+
+const A98_GAMMA: f32 = 563.0 / 256.0;
+
+pub(crate) fn a98_to_linear(x: f32) -> f32 {
+    x.signum() * x.abs().powf(A98_GAMMA)
+}
+
+pub(crate) fn a98_from_linear(x: f32) -> f32 {
+    x.signum() * x.abs().powf(1.0 / A98_GAMMA)
+}
+
+pub(crate) fn prophoto_to_linear(x: f32) -> f32 {
+    if x.abs() <= 16.0 / 512.0 {
+        return x / 16.0;
+    }
+    x.signum() * x.abs().powf(1.8)
+}
+
+pub(crate) fn prophoto_from_linear(x: f32) -> f32 {
+    if x.abs() >= 1.0 / 512.0 {
+        return x.signum() * x.abs().powf(1.0 / 1.8);
+    }
+    16.0 * x
+}
+
+const REC2020_ALPHA: f32 = 1.09929682680944;
+const REC2020_BETA: f32 = 0.018053968510807;
+
+pub(crate) fn rec2020_to_linear(x: f32) -> f32 {
+    if x.abs() < REC2020_BETA * 4.5 {
+        return x / 4.5;
+    }
+    x.signum() * ((x.abs() + REC2020_ALPHA - 1.0) / REC2020_ALPHA).powf(1.0 / 0.45)
+}
+
+pub(crate) fn rec2020_from_linear(x: f32) -> f32 {
+    if x.abs() > REC2020_BETA {
+        return x.signum() * (REC2020_ALPHA * x.abs().powf(0.45) - (REC2020_ALPHA - 1.0));
+    }
+    4.5 * x
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hue_fixup() {
+        use HueInterpolation::*;
+        let test_data = [
+            (Shorter, 10.0, 350.0, (370.0, 350.0)),
+            (Shorter, 350.0, 10.0, (350.0, 370.0)),
+            (Shorter, 10.0, 90.0, (10.0, 90.0)),
+            (Longer, 10.0, 90.0, (370.0, 90.0)),
+            (Longer, 90.0, 10.0, (90.0, 370.0)),
+            (Longer, 10.0, 350.0, (10.0, 350.0)),
+            (Increasing, 90.0, 10.0, (90.0, 370.0)),
+            (Increasing, 10.0, 90.0, (10.0, 90.0)),
+            (Decreasing, 10.0, 90.0, (370.0, 90.0)),
+            (Decreasing, 90.0, 10.0, (90.0, 10.0)),
+            (Shorter, -90.0, 720.0, (270.0, 360.0)),
+        ];
+        for (method, h1, h2, expected) in test_data {
+            assert_eq!(method.fixup(h1, h2), expected);
+        }
+    }
+
+    #[test]
+    fn test_convert_missing() {
+        let nan = f32::NAN;
+
+        // red -> x, y and z are not missing
+        let c = ColorSpace::XyzD65.convert(ColorSpace::Srgb, [nan, 0.0, 0.0, 1.0]);
+        assert!(c[0].is_nan());
+        assert!(!c[1].is_nan() && !c[2].is_nan() && !c[3].is_nan());
+
+        // lightness and hue carried over
+        let c = ColorSpace::Oklch.convert(ColorSpace::Hsl, [nan, 0.5, nan, 1.0]);
+        assert!(c[0].is_nan() && !c[1].is_nan() && c[2].is_nan());
+
+        // powerless hue
+        let c = ColorSpace::Hsl.convert(ColorSpace::Srgb, [0.5, 0.5, 0.5, 1.0]);
+        assert!(c[0].is_nan());
+        let c = ColorSpace::Oklch.convert(ColorSpace::Srgb, [0.2, 0.2, 0.2, nan]);
+        assert!(c[2].is_nan() && c[3].is_nan());
+        let c = ColorSpace::Hwb.convert(ColorSpace::Srgb, [0.2, 0.4, 0.6, 1.0]);
+        assert!(!c[0].is_nan());
+
+        // same color space, nothing changed
+        let c = ColorSpace::Hsl.convert(ColorSpace::Hsl, [120.0, 0.0, 0.5, 1.0]);
+        assert_eq!(c, [120.0, 0.0, 0.5, 1.0]);
+    }
+
+    #[test]
+    fn test_inverse_matrices() {
+        let pairs = [
+            (SRGB_TO_XYZ_D65, XYZ_D65_TO_SRGB),
+            (DISPLAY_P3_TO_XYZ_D65, XYZ_D65_TO_DISPLAY_P3),
+            (A98_RGB_TO_XYZ_D65, XYZ_D65_TO_A98_RGB),
+            (REC2020_TO_XYZ_D65, XYZ_D65_TO_REC2020),
+            (PROPHOTO_RGB_TO_XYZ_D50, XYZ_D50_TO_PROPHOTO_RGB),
+            (D65_TO_D50, D50_TO_D65),
+        ];
+        for (a, b) in pairs {
+            let m = mul33(&a, &b);
+            for (i, row) in m.iter().enumerate() {
+                for (j, v) in row.iter().enumerate() {
+                    let expected = if i == j { 1.0 } else { 0.0 };
+                    assert!((v - expected).abs() < 1e-6);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_transfer_functions() {
+        type Transfer = fn(f32) -> f32;
+        let fns: [(Transfer, Transfer); 4] = [
+            (srgb_to_linear, srgb_from_linear),
+            (a98_to_linear, a98_from_linear),
+            (prophoto_to_linear, prophoto_from_linear),
+            (rec2020_to_linear, rec2020_from_linear),
+        ];
+        for (to_linear, from_linear) in fns {
+            for x in [0.0, 0.01, 0.1, 0.25, 0.5, 0.75, 1.0] {
+                assert!((from_linear(to_linear(x)) - x).abs() < 1e-5);
+            }
+        }
+    }
+}
@@ -0,0 +1,206 @@
+// Context-dependent colors: `currentcolor`, system colors and `light-dark()`.
+// https://www.w3.org/TR/css-color-4/#css-system-colors
+
+use crate::Color;
+
+/// Color scheme, picks the color of `light-dark()` and the default system colors.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum ColorScheme {
+    #[default]
+    Light,
+    Dark,
+}
+
+/// CSS system colors, e.g. `Canvas` or `LinkText`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SystemColor {
+    AccentColor,
+    AccentColorText,
+    ActiveText,
+    ButtonBorder,
+    ButtonFace,
+    ButtonText,
+    Canvas,
+    CanvasText,
+    Field,
+    FieldText,
+    GrayText,
+    Highlight,
+    HighlightText,
+    LinkText,
+    Mark,
+    MarkText,
+    SelectedItem,
+    SelectedItemText,
+    VisitedText,
+}
+
+const SYSTEM_COLORS: usize = 19;
+
+// Lowercase names, including the deprecated system colors
+const NAMES: &[(&str, SystemColor)] = {
+    use SystemColor::*;
+    &[
+        ("accentcolor", AccentColor),
+        ("accentcolortext", AccentColorText),
+        ("activetext", ActiveText),
+        ("buttonborder", ButtonBorder),
+        ("buttonface", ButtonFace),
+        ("buttontext", ButtonText),
+        ("canvas", Canvas),
+        ("canvastext", CanvasText),
+        ("field", Field),
+        ("fieldtext", FieldText),
+        ("graytext", GrayText),
+        ("highlight", Highlight),
+        ("highlighttext", HighlightText),
+        ("linktext", LinkText),
+        ("mark", Mark),
+        ("marktext", MarkText),
+        ("selecteditem", SelectedItem),
+        ("selecteditemtext", SelectedItemText),
+        ("visitedtext", VisitedText),
+        // https://www.w3.org/TR/css-color-4/#deprecated-system-colors
+        ("activeborder", ButtonBorder),
+        ("activecaption", Canvas),
+        ("appworkspace", Canvas),
+        ("background", Canvas),
+        ("buttonhighlight", ButtonFace),
+        ("buttonshadow", ButtonFace),
+        ("captiontext", CanvasText),
+        ("inactiveborder", ButtonBorder),
+        ("inactivecaption", Canvas),
+        ("inactivecaptiontext", GrayText),
+        ("infobackground", Canvas),
+        ("infotext", CanvasText),
+        ("menu", Canvas),
+        ("menutext", CanvasText),
+        ("scrollbar", Canvas),
+        ("threeddarkshadow", ButtonBorder),
+        ("threedface", ButtonFace),
+        ("threedhighlight", ButtonBorder),
+        ("threedlightshadow", ButtonBorder),
+        ("threedshadow", ButtonBorder),
+        ("window", Canvas),
+        ("windowframe", ButtonBorder),
+        ("windowtext", CanvasText),
+    ]
+};
+
+// Default palettes, in the order of `SystemColor`
+const LIGHT: [[u8; 3]; SYSTEM_COLORS] = [
+    [0, 117, 255],
+    [255, 255, 255],
+    [255, 0, 0],
+    [118, 118, 118],
+    [239, 239, 239],
+    [0, 0, 0],
+    [255, 255, 255],
+    [0, 0, 0],
+    [255, 255, 255],
+    [0, 0, 0],
+    [128, 128, 128],
+    [51, 144, 255],
+    [255, 255, 255],
+    [0, 0, 238],
+    [255, 255, 0],
+    [0, 0, 0],
+    [0, 117, 255],
+    [255, 255, 255],
+    [85, 26, 139],
+];
+
+const DARK: [[u8; 3]; SYSTEM_COLORS] = [
+    [153, 200, 255],
+    [0, 0, 0],
+    [255, 158, 158],
+    [107, 107, 107],
+    [107, 107, 107],
+    [255, 255, 255],
+    [18, 18, 18],
+    [255, 255, 255],
+    [59, 59, 59],
+    [255, 255, 255],
+    [128, 128, 128],
+    [51, 144, 255],
+    [255, 255, 255],
+    [158, 158, 255],
+    [255, 255, 0],
+    [0, 0, 0],
+    [153, 200, 255],
+    [59, 59, 59],
+    [208, 173, 240],
+];
+
+impl SystemColor {
+    /// Parse the name of a system color, ASCII case-insensitive. The
+    /// deprecated system colors, e.g. `ButtonHighlight`, map to their
+    /// replacement.
+    pub fn from_name(name: &str) -> Option<Self> {
+        NAMES
+            .iter()
+            .find(|(s, _)| s.eq_ignore_ascii_case(name))
+            .map(|&(_, c)| c)
+    }
+}
+
+/// Resolution context of the colors that depend on where they are used:
+/// `currentcolor`, system colors and `light-dark()`.
+///
+/// # Examples
+///
+/// ```
+/// use csscolorparser::{parse_with_context, Color, ColorScheme, Context, SystemColor};
+///
+/// let ctx = Context::new(ColorScheme::Dark)
+///     .current_color(Color::new(1.0, 0.0, 0.0, 1.0))
+///     .system_color(SystemColor::LinkText, Color::new(0.0, 0.0, 1.0, 1.0));
+///
+/// assert_eq!(parse_with_context("currentcolor", &ctx).unwrap().to_rgba8(), [255, 0, 0, 255]);
+/// assert_eq!(parse_with_context("LinkText", &ctx).unwrap().to_rgba8(), [0, 0, 255, 255]);
+/// assert_eq!(parse_with_context("light-dark(#fff, #000)", &ctx).unwrap().to_rgba8(), [0, 0, 0, 255]);
+///
+/// // Without a context
+/// assert!(csscolorparser::parse("currentcolor").is_err());
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Context {
+    pub(crate) current_color: Color,
+    pub(crate) color_scheme: ColorScheme,
+    pub(crate) system_colors: [Color; SYSTEM_COLORS],
+}
+
+impl Context {
+    /// Context with the default system colors of the color scheme, and
+    /// `CanvasText` as the current color.
+    pub fn new(color_scheme: ColorScheme) -> Self {
+        let palette = match color_scheme {
+            ColorScheme::Light => LIGHT,
+            ColorScheme::Dark => DARK,
+        };
+        let system_colors = palette.map(|[r, g, b]| Color::from_rgba8(r, g, b, 255));
+        Self {
+            current_color: system_colors[SystemColor::CanvasText as usize].clone(),
+            color_scheme,
+            system_colors,
+        }
+    }
+
+    /// Set the value of `currentcolor`.
+    pub fn current_color(mut self, color: Color) -> Self {
+        self.current_color = color;
+        self
+    }
+
+    /// Set the value of a system color.
+    pub fn system_color(mut self, name: SystemColor, color: Color) -> Self {
+        self.system_colors[name as usize] = color;
+        self
+    }
+}
+
+impl Default for Context {
+    fn default() -> Self {
+        Self::new(ColorScheme::Light)
+    }
+}
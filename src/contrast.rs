@@ -0,0 +1,86 @@
+// Contrast of text on a background, WCAG 2.x and APCA.
+// https://www.w3.org/TR/WCAG21/#dfn-contrast-ratio
+// https://github.com/Myndex/apca-w3
+
+#[cfg(not(any(feature = "std", test)))]
+use crate::math::FloatExt;
+use crate::Color;
+
+/// WCAG 2.x conformance level of [`Color::meets_wcag()`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum WcagLevel {
+    /// Level AA, contrast ratio of at least 4.5:1, 3:1 for large text.
+    Aa,
+    /// Level AAA, contrast ratio of at least 7:1, 4.5:1 for large text.
+    Aaa,
+}
+
+/// Text size of [`Color::meets_wcag()`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum TextSize {
+    #[default]
+    Normal,
+    /// At least 18 point, or 14 point bold.
+    Large,
+}
+
+impl WcagLevel {
+    /// The minimum contrast ratio of this level for the text size
+    pub fn min_contrast_ratio(self, size: TextSize) -> f32 {
+        match (self, size) {
+            (Self::Aa, TextSize::Normal) => 4.5,
+            (Self::Aa, TextSize::Large) => 3.0,
+            (Self::Aaa, TextSize::Normal) => 7.0,
+            (Self::Aaa, TextSize::Large) => 4.5,
+        }
+    }
+}
+
+// A translucent background is shown over the white canvas of a page.
+pub(crate) fn opaque(background: &Color) -> Color {
+    background.composite(&Color::new(1.0, 1.0, 1.0, 1.0))
+}
+
+pub(crate) fn contrast_ratio(text: &Color, background: &Color) -> f32 {
+    let background = opaque(background);
+    let a = text.relative_luminance_over(&background);
+    let b = background.relative_luminance();
+    (a.max(b) + 0.05) / (a.min(b) + 0.05)
+}
+
+// APCA 0.0.98G-4g
+pub(crate) fn apca(text: &Color, background: &Color) -> f32 {
+    let luminance = |c: &Color| {
+        let y = 0.2126729 * c.r.powf(2.4) + 0.7151522 * c.g.powf(2.4) + 0.0721750 * c.b.powf(2.4);
+        // Soft clamp of the black level
+        if y <= 0.022 {
+            y + (0.022 - y).powf(1.414)
+        } else {
+            y
+        }
+    };
+    let background = opaque(background);
+    let text = luminance(&text.composite(&background).clamp());
+    let background = luminance(&background.clamp());
+
+    if (background - text).abs() < 0.0005 {
+        return 0.0;
+    }
+    let lc = if background > text {
+        // Dark text on a light background
+        let sapc = (background.powf(0.56) - text.powf(0.57)) * 1.14;
+        if sapc < 0.1 {
+            0.0
+        } else {
+            sapc - 0.027
+        }
+    } else {
+        let sapc = (background.powf(0.65) - text.powf(0.62)) * 1.14;
+        if sapc > -0.1 {
+            0.0
+        } else {
+            sapc + 0.027
+        }
+    };
+    lc * 100.0
+}
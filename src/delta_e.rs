@@ -0,0 +1,287 @@
+// Color difference metrics, in CIE Lab relative to D50 like CSS, Oklab and
+// ICtCp.
+
+use crate::colorspace::{mul3, SRGB_TO_XYZ_D65, XYZ_D65_TO_REC2020};
+#[cfg(not(any(feature = "std", test)))]
+use crate::math::FloatExt;
+use crate::Color;
+
+/// Color difference metric of [`Color::distance()`]
+///
+/// The metrics for industry, CIE94 and CMC, are not symmetric: the color
+/// `distance()` is called on is the reference.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum DeltaE {
+    /// CIE76, the Euclidean distance in CIE Lab.
+    Cie76,
+    /// CIE94 with the weights for graphic arts.
+    Cie94GraphicArts,
+    /// CIE94 with the weights for textiles.
+    Cie94Textiles,
+    /// CIEDE2000.
+    #[default]
+    Ciede2000,
+    /// CMC l:c, commonly 2:1 for acceptability and 1:1 for perceptibility.
+    Cmc { l: f32, c: f32 },
+    /// deltaEOK, the Euclidean distance in Oklab used by CSS gamut mapping.
+    /// A just noticeable difference is about 0.02.
+    Ok,
+    /// ΔE ITP of ITU-R BT.2124, with the white of SDR content at 203 cd/m².
+    Itp,
+}
+
+impl DeltaE {
+    pub(crate) fn between(self, reference: &Color, sample: &Color) -> f32 {
+        let lab = |c: &Color| {
+            let [l, a, b, _] = c.to_laba();
+            [l, a, b]
+        };
+        match self {
+            Self::Cie76 => euclidean(lab(reference), lab(sample)),
+            Self::Cie94GraphicArts => cie94(lab(reference), lab(sample), 1.0, 0.045, 0.015),
+            Self::Cie94Textiles => cie94(lab(reference), lab(sample), 2.0, 0.048, 0.014),
+            Self::Ciede2000 => ciede2000(lab(reference), lab(sample)),
+            Self::Cmc { l, c } => cmc(lab(reference), lab(sample), l, c),
+            Self::Ok => {
+                let oklab = |c: &Color| {
+                    let [l, a, b, _] = c.to_oklaba();
+                    [l, a, b]
+                };
+                euclidean(oklab(reference), oklab(sample))
+            }
+            Self::Itp => {
+                let [i1, t1, p1] = ictcp(reference);
+                let [i2, t2, p2] = ictcp(sample);
+                720.0 * euclidean([i1, t1 / 2.0, p1], [i2, t2 / 2.0, p2])
+            }
+        }
+    }
+}
+
+fn euclidean([a1, b1, c1]: [f32; 3], [a2, b2, c2]: [f32; 3]) -> f32 {
+    let (d1, d2, d3) = (a1 - a2, b1 - b2, c1 - c2);
+    (d1 * d1 + d2 * d2 + d3 * d3).sqrt()
+}
+
+// The squared hue difference of CIE94 and CMC, from the chroma difference
+fn hue_difference_squared([_, a1, b1]: [f32; 3], [_, a2, b2]: [f32; 3], delta_c: f32) -> f32 {
+    let (da, db) = (a1 - a2, b1 - b2);
+    (da * da + db * db - delta_c * delta_c).max(0.0)
+}
+
+fn cie94(lab1: [f32; 3], lab2: [f32; 3], kl: f32, k1: f32, k2: f32) -> f32 {
+    let c1 = lab1[1].hypot(lab1[2]);
+    let c2 = lab2[1].hypot(lab2[2]);
+    let dl = (lab1[0] - lab2[0]) / kl;
+    let dc = (c1 - c2) / (1.0 + k1 * c1);
+    let dh2 = hue_difference_squared(lab1, lab2, c1 - c2) / (1.0 + k2 * c1).powi(2);
+    (dl * dl + dc * dc + dh2).sqrt()
+}
+
+fn cmc(lab1: [f32; 3], lab2: [f32; 3], l: f32, c: f32) -> f32 {
+    let [l1, a1, b1] = lab1;
+    let c1 = a1.hypot(b1);
+    let c2 = lab2[1].hypot(lab2[2]);
+    let h1 = b1.atan2(a1).to_degrees().rem_euclid(360.0);
+
+    let sl = if l1 < 16.0 {
+        0.511
+    } else {
+        0.040975 * l1 / (1.0 + 0.01765 * l1)
+    };
+    let sc = 0.0638 * c1 / (1.0 + 0.0131 * c1) + 0.638;
+    let t = if (164.0..=345.0).contains(&h1) {
+        0.56 + (0.2 * (h1 + 168.0).to_radians().cos()).abs()
+    } else {
+        0.36 + (0.4 * (h1 + 35.0).to_radians().cos()).abs()
+    };
+    let c4 = c1.powi(4);
+    let f = (c4 / (c4 + 1900.0)).sqrt();
+    let sh = sc * (f * t + 1.0 - f);
+
+    let dl = (l1 - lab2[0]) / (l * sl);
+    let dc = (c1 - c2) / (c * sc);
+    let dh2 = hue_difference_squared(lab1, lab2, c1 - c2) / (sh * sh);
+    (dl * dl + dc * dc + dh2).sqrt()
+}
+
+// http://www2.ece.rochester.edu/~gsharma/ciede2000/
+fn ciede2000([l1, a1, b1]: [f32; 3], [l2, a2, b2]: [f32; 3]) -> f32 {
+    // 25^7
+    const K: f32 = 6103515625.0;
+
+    let c_mean = (a1.hypot(b1) + a2.hypot(b2)) / 2.0;
+    let c7 = c_mean.powi(7);
+    let g = 0.5 * (1.0 - (c7 / (c7 + K)).sqrt());
+    let (a1, a2) = (a1 * (1.0 + g), a2 * (1.0 + g));
+    let (c1, c2) = (a1.hypot(b1), a2.hypot(b2));
+    let hue = |a: f32, b: f32| {
+        if a == 0.0 && b == 0.0 {
+            0.0
+        } else {
+            b.atan2(a).to_degrees().rem_euclid(360.0)
+        }
+    };
+    let (h1, h2) = (hue(a1, b1), hue(a2, b2));
+    let chromatic = c1 * c2 != 0.0;
+
+    let dl = l2 - l1;
+    let dc = c2 - c1;
+    let dh = if !chromatic {
+        0.0
+    } else if h2 - h1 > 180.0 {
+        h2 - h1 - 360.0
+    } else if h2 - h1 < -180.0 {
+        h2 - h1 + 360.0
+    } else {
+        h2 - h1
+    };
+    let dh = 2.0 * (c1 * c2).sqrt() * (dh / 2.0).to_radians().sin();
+
+    let l_mean = (l1 + l2) / 2.0;
+    let c_mean = (c1 + c2) / 2.0;
+    let h_mean = if !chromatic {
+        h1 + h2
+    } else if (h1 - h2).abs() <= 180.0 {
+        (h1 + h2) / 2.0
+    } else if h1 + h2 < 360.0 {
+        (h1 + h2 + 360.0) / 2.0
+    } else {
+        (h1 + h2 - 360.0) / 2.0
+    };
+
+    let cos = |deg: f32| deg.to_radians().cos();
+    let t =
+        1.0 - 0.17 * cos(h_mean - 30.0) + 0.24 * cos(2.0 * h_mean) + 0.32 * cos(3.0 * h_mean + 6.0)
+            - 0.20 * cos(4.0 * h_mean - 63.0);
+    let d_theta = 30.0 * (-((h_mean - 275.0) / 25.0).powi(2)).exp();
+    let c7 = c_mean.powi(7);
+    let rc = 2.0 * (c7 / (c7 + K)).sqrt();
+    let l50 = (l_mean - 50.0).powi(2);
+    let sl = 1.0 + 0.015 * l50 / (20.0 + l50).sqrt();
+    let sc = 1.0 + 0.045 * c_mean;
+    let sh = 1.0 + 0.015 * c_mean * t;
+    let rt = -(2.0 * d_theta).to_radians().sin() * rc;
+
+    let (dl, dc, dh) = (dl / sl, dc / sc, dh / sh);
+    (dl * dl + dc * dc + dh * dh + rt * dc * dh).sqrt()
+}
+
+// ICtCp of ITU-R BT.2100 with the PQ curve
+fn ictcp(c: &Color) -> [f32; 3] {
+    const M1: f32 = 2610.0 / 16384.0;
+    const M2: f32 = 2523.0 / 4096.0 * 128.0;
+    const C1: f32 = 3424.0 / 4096.0;
+    const C2: f32 = 2413.0 / 4096.0 * 32.0;
+    const C3: f32 = 2392.0 / 4096.0 * 32.0;
+    // The white of SDR content in cd/m², of the 10000 of PQ
+    const WHITE: f32 = 203.0 / 10000.0;
+
+    let [r, g, b, _] = c.to_linear_rgba();
+    let rgb = mul3(&XYZ_D65_TO_REC2020, mul3(&SRGB_TO_XYZ_D65, [r, g, b]));
+    let lms = mul3(
+        &[
+            [1688.0 / 4096.0, 2146.0 / 4096.0, 262.0 / 4096.0],
+            [683.0 / 4096.0, 2951.0 / 4096.0, 462.0 / 4096.0],
+            [99.0 / 4096.0, 309.0 / 4096.0, 3688.0 / 4096.0],
+        ],
+        rgb,
+    );
+    let [l, m, s] = lms.map(|t| {
+        let y = (t * WHITE).abs().powf(M1);
+        t.signum() * ((C1 + C2 * y) / (1.0 + C3 * y)).powf(M2)
+    });
+    [
+        0.5 * l + 0.5 * m,
+        (6610.0 * l - 13613.0 * m + 7003.0 * s) / 4096.0,
+        (17933.0 * l - 17390.0 * m - 543.0 * s) / 4096.0,
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ciede2000_sharma() {
+        // G. Sharma, W. Wu, E. N. Dalal, "The CIEDE2000 color-difference
+        // formula: Implementation notes, supplementary test data, and
+        // mathematical observations", 2005
+        #[rustfmt::skip]
+        let test_data = [
+            ([50.0, 2.6772, -79.7751], [50.0, 0.0, -82.7485], 2.0425),
+            ([50.0, 3.1571, -77.2803], [50.0, 0.0, -82.7485], 2.8615),
+            ([50.0, 2.8361, -74.0200], [50.0, 0.0, -82.7485], 3.4412),
+            ([50.0, -1.3802, -84.2814], [50.0, 0.0, -82.7485], 1.0000),
+            ([50.0, -1.1848, -84.8006], [50.0, 0.0, -82.7485], 1.0000),
+            ([50.0, -0.9009, -85.5211], [50.0, 0.0, -82.7485], 1.0000),
+            ([50.0, 0.0, 0.0], [50.0, -1.0, 2.0], 2.3669),
+            ([50.0, -1.0, 2.0], [50.0, 0.0, 0.0], 2.3669),
+            ([50.0, 2.4900, -0.0010], [50.0, -2.4900, 0.0009], 7.1792),
+            ([50.0, 2.4900, -0.0010], [50.0, -2.4900, 0.0010], 7.1792),
+            ([50.0, 2.4900, -0.0010], [50.0, -2.4900, 0.0011], 7.2195),
+            ([50.0, 2.4900, -0.0010], [50.0, -2.4900, 0.0012], 7.2195),
+            ([50.0, -0.0010, 2.4900], [50.0, 0.0009, -2.4900], 4.8045),
+            ([50.0, -0.0010, 2.4900], [50.0, 0.0010, -2.4900], 4.8045),
+            ([50.0, -0.0010, 2.4900], [50.0, 0.0011, -2.4900], 4.7461),
+            ([50.0, 2.5, 0.0], [50.0, 0.0, -2.5], 4.3065),
+            ([50.0, 2.5, 0.0], [73.0, 25.0, -18.0], 27.1492),
+            ([50.0, 2.5, 0.0], [61.0, -5.0, 29.0], 22.8977),
+            ([50.0, 2.5, 0.0], [56.0, -27.0, -3.0], 31.9030),
+            ([50.0, 2.5, 0.0], [58.0, 24.0, 15.0], 19.4535),
+            ([50.0, 2.5, 0.0], [50.0, 3.1736, 0.5854], 1.0000),
+            ([50.0, 2.5, 0.0], [50.0, 3.2972, 0.0], 1.0000),
+            ([50.0, 2.5, 0.0], [50.0, 1.8634, 0.5757], 1.0000),
+            ([50.0, 2.5, 0.0], [50.0, 3.2592, 0.3350], 1.0000),
+            ([60.2574, -34.0099, 36.2677], [60.4626, -34.1751, 39.4387], 1.2644),
+            ([63.0109, -31.0961, -5.8663], [62.8187, -29.7946, -4.0864], 1.2630),
+            ([61.2901, 3.7196, -5.3901], [61.4292, 2.2480, -4.9620], 1.8731),
+            ([35.0831, -44.1164, 3.7933], [35.0232, -40.0716, 1.5901], 1.8645),
+            ([22.7233, 20.0904, -46.6940], [23.0331, 14.9730, -42.5619], 2.0373),
+            ([36.4612, 47.8580, 18.3852], [36.2715, 50.5065, 21.2231], 1.4146),
+            ([90.8027, -2.0831, 1.4410], [91.1528, -1.6435, 0.0447], 1.4441),
+            ([90.9257, -0.5406, -0.9208], [88.6381, -0.8985, -0.7239], 1.5381),
+            ([6.7747, -0.2908, -2.4247], [5.8714, -0.0985, -2.2286], 0.6377),
+            ([2.0776, 0.0795, -1.1350], [0.9033, -0.0636, -0.5514], 0.9082),
+        ];
+        for (lab1, lab2, expected) in test_data {
+            let d = ciede2000(lab1, lab2);
+            assert!((d - expected).abs() < 1e-4, "{:?} {:?}: {}", lab1, lab2, d);
+            assert!((ciede2000(lab2, lab1) - expected).abs() < 1e-4);
+        }
+    }
+
+    #[test]
+    fn industry_metrics() {
+        // The reference first, both branches of the hue weight of CMC
+        let test_data = [
+            (
+                [50.0, 2.5, 0.0],
+                [73.0, 25.0, -18.0],
+                [34.6892, 28.2503, 37.9233, 42.1088],
+            ),
+            (
+                [73.0, 25.0, -18.0],
+                [50.0, 2.5, 0.0],
+                [26.1398, 16.6382, 16.874, 22.7367],
+            ),
+        ];
+        for (lab1, lab2, expected) in test_data {
+            let d = [
+                cie94(lab1, lab2, 1.0, 0.045, 0.015),
+                cie94(lab1, lab2, 2.0, 0.048, 0.014),
+                cmc(lab1, lab2, 2.0, 1.0),
+                cmc(lab1, lab2, 1.0, 1.0),
+            ];
+            for (d, expected) in d.iter().zip(expected.iter()) {
+                assert!(
+                    (d - expected).abs() < 1e-3,
+                    "{:?} {:?}: {:?}",
+                    lab1,
+                    lab2,
+                    d
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,206 @@
+// Color literals of other platforms, which have their own channel order.
+
+#[cfg(feature = "alloc")]
+use alloc::{format, string::String};
+
+use crate::{parse, Color, ParseColorError};
+
+/// Color notation of a platform, for [`parse_dialect()`] and
+/// [`Color::to_dialect_string()`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Dialect {
+    /// CSS colors, the same as [`parse()`] and [`Color::to_hex_string()`].
+    Css,
+    /// Android, alpha first: `#AARRGGBB`, `#RRGGBB`, `#ARGB` and `#RGB`.
+    Android,
+    /// Flutter: `Color(0xAARRGGBB)`, `0xAARRGGBB`, `Color.fromARGB(a, r, g, b)`
+    /// and `Color.fromRGBO(r, g, b, opacity)`.
+    Flutter,
+    /// Qt: `QColor(r, g, b, a)`, `QColor("#AARRGGBB")`, `QColor("#RGB")` and
+    /// `QColor::fromRgbF(r, g, b, a)`, alpha is optional.
+    Qt,
+    /// .NET: `Color.FromArgb(a, r, g, b)` and `Color.FromArgb(r, g, b)`.
+    DotNet,
+    /// UIKit: `UIColor(red: r, green: g, blue: b, alpha: a)`, components
+    /// in the range [0..1].
+    UiKit,
+}
+
+/// Parse color in the notation of a platform
+///
+/// # Examples
+///
+/// ```
+/// use csscolorparser::{parse_dialect, Dialect};
+///
+/// let c = parse_dialect("#80FF0000", Dialect::Android).unwrap();
+/// assert_eq!(c.to_rgba8(), [255, 0, 0, 128]);
+///
+/// let c = parse_dialect("0xFF336699", Dialect::Flutter).unwrap();
+/// assert_eq!(c.to_rgba8(), [51, 102, 153, 255]);
+///
+/// let c = parse_dialect("UIColor(red: 1, green: 0.5, blue: 0, alpha: 1)", Dialect::UiKit).unwrap();
+/// assert_eq!(c.to_rgba8(), [255, 128, 0, 255]);
+/// ```
+pub fn parse_dialect(s: &str, dialect: Dialect) -> Result<Color, ParseColorError> {
+    match dialect {
+        Dialect::Css => parse(s),
+        Dialect::Android => parse_platform(s.trim(), dialect).ok_or(ParseColorError::InvalidHex),
+        _ => parse_platform(s.trim(), dialect).ok_or(ParseColorError::InvalidUnknown),
+    }
+}
+
+fn parse_platform(s: &str, dialect: Dialect) -> Option<Color> {
+    let mut args = [""; 4];
+    let argb = |[a, r, g, b]: [u8; 4]| Color::from_rgba8(r, g, b, a);
+
+    match dialect {
+        Dialect::Css => parse(s).ok(),
+        Dialect::Android => hex_argb(s.strip_prefix('#')?, true).map(argb),
+        Dialect::Flutter => {
+            match call(s, &["Color", "Color.fromARGB", "Color.fromRGBO"], &mut args) {
+                Some(("Color", [hex])) => flutter_int(hex).map(argb),
+                Some(("Color.fromARGB", [a, r, g, b])) => {
+                    Some(Color::from_rgba8(int(r)?, int(g)?, int(b)?, int(a)?))
+                }
+                Some(("Color.fromRGBO", [r, g, b, o])) => {
+                    let o = float(o).filter(|o| (0.0..=1.0).contains(o))?;
+                    let [r, g, b, _] = Color::from_rgba8(int(r)?, int(g)?, int(b)?, 255).to_array();
+                    Some(Color::new(r, g, b, o))
+                }
+                Some(_) => None,
+                None => flutter_int(s).map(argb),
+            }
+        }
+        Dialect::Qt => match call(
+            s,
+            &["QColor", "QColor::fromRgb", "QColor::fromRgbF"],
+            &mut args,
+        ) {
+            Some(("QColor", [name])) => {
+                let name = name.strip_prefix('"')?.strip_suffix('"')?;
+                match name.strip_prefix('#') {
+                    Some(hex) => hex_argb(hex, false).map(argb),
+                    None => parse(name).ok(),
+                }
+            }
+            Some(("QColor" | "QColor::fromRgb", [r, g, b, rest @ ..])) if rest.len() < 2 => {
+                let a = rest.first().map_or(Some(255), |a| int(a))?;
+                Some(Color::from_rgba8(int(r)?, int(g)?, int(b)?, a))
+            }
+            Some(("QColor::fromRgbF", [r, g, b, rest @ ..])) if rest.len() < 2 => {
+                let a = rest.first().map_or(Some(1.0), |a| float(a))?;
+                fraction_rgba([float(r)?, float(g)?, float(b)?, a])
+            }
+            _ => None,
+        },
+        Dialect::DotNet => match call(s, &["Color.FromArgb", "FromArgb"], &mut args) {
+            Some((_, [a, r, g, b])) => Some(Color::from_rgba8(int(r)?, int(g)?, int(b)?, int(a)?)),
+            Some((_, [r, g, b])) => Some(Color::from_rgba8(int(r)?, int(g)?, int(b)?, 255)),
+            _ => None,
+        },
+        Dialect::UiKit => match call(s, &["UIColor", "NSColor"], &mut args) {
+            Some((_, [r, g, b, a])) => {
+                let arg = |s: &str, label: &str| float(s.strip_prefix(label)?.trim_start());
+                fraction_rgba([
+                    arg(r, "red:")?,
+                    arg(g, "green:")?,
+                    arg(b, "blue:")?,
+                    arg(a, "alpha:")?,
+                ])
+            }
+            _ => None,
+        },
+    }
+}
+
+impl Dialect {
+    #[cfg(feature = "alloc")]
+    pub(crate) fn format(self, c: &Color) -> String {
+        let [r, g, b, a] = c.to_rgba8();
+        let [rf, gf, bf, af] = c.to_array().map(|t| fraction(t.clamp(0.0, 1.0)));
+        match self {
+            Self::Css => c.to_hex_string(),
+            Self::Android if a == 255 => format!("#{:02X}{:02X}{:02X}", r, g, b),
+            Self::Android => format!("#{:02X}{:02X}{:02X}{:02X}", a, r, g, b),
+            Self::Flutter => format!("Color(0x{:02X}{:02X}{:02X}{:02X})", a, r, g, b),
+            Self::Qt => format!("QColor({}, {}, {}, {})", r, g, b, a),
+            Self::DotNet => format!("Color.FromArgb({}, {}, {}, {})", a, r, g, b),
+            Self::UiKit => format!(
+                "UIColor(red: {}, green: {}, blue: {}, alpha: {})",
+                rf, gf, bf, af
+            ),
+        }
+    }
+}
+
+// `name(a, b, ...)` with one of `names`, returns the name and the trimmed
+// arguments, at most 4.
+fn call<'a, 'n, 'b>(
+    s: &'a str,
+    names: &[&'n str],
+    args: &'b mut [&'a str; 4],
+) -> Option<(&'n str, &'b [&'a str])> {
+    let (name, rest) = s.split_once('(')?;
+    let name = names.iter().find(|n| **n == name.trim_end())?;
+    let body = rest.strip_suffix(')')?;
+    let mut len = 0;
+    for arg in body.split(',') {
+        *args.get_mut(len)? = arg.trim();
+        len += 1;
+    }
+    Some((name, &args[..len]))
+}
+
+// `AARRGGBB`, `RRGGBB`, `RGB`, and `ARGB` if `short_alpha`
+fn hex_argb(hex: &str, short_alpha: bool) -> Option<[u8; 4]> {
+    if !hex.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return None;
+    }
+    let digit = |i: usize| u8::from_str_radix(&hex[i..i + 1], 16).ok().map(|t| t * 17);
+    let byte = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+    match hex.len() {
+        3 => Some([255, digit(0)?, digit(1)?, digit(2)?]),
+        4 if short_alpha => Some([digit(0)?, digit(1)?, digit(2)?, digit(3)?]),
+        6 => Some([255, byte(0)?, byte(2)?, byte(4)?]),
+        8 => Some([byte(0)?, byte(2)?, byte(4)?, byte(6)?]),
+        _ => None,
+    }
+}
+
+// `0xAARRGGBB`, missing digits are zero like in Dart
+fn flutter_int(s: &str) -> Option<[u8; 4]> {
+    let hex = s.strip_prefix("0x").or_else(|| s.strip_prefix("0X"))?;
+    if hex.is_empty() || hex.len() > 8 || !hex.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return None;
+    }
+    u32::from_str_radix(hex, 16).ok().map(u32::to_be_bytes)
+}
+
+// Decimal digits only, `parse()` also takes a leading `+`
+fn int(s: &str) -> Option<u8> {
+    if s.is_empty() || !s.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    s.parse().ok()
+}
+
+fn float(s: &str) -> Option<f32> {
+    s.parse().ok().filter(|t: &f32| t.is_finite())
+}
+
+fn fraction_rgba(rgba: [f32; 4]) -> Option<Color> {
+    if !rgba.iter().all(|t| (0.0..=1.0).contains(t)) {
+        return None;
+    }
+    Some(Color::from(rgba))
+}
+
+// Enough digits to round-trip 8-bit components
+#[cfg(feature = "alloc")]
+fn fraction(t: f32) -> f32 {
+    #[cfg(not(any(feature = "std", test)))]
+    use crate::math::FloatExt;
+
+    (t * 1000.0).round() / 1000.0
+}
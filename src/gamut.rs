@@ -0,0 +1,248 @@
+// Gamut mapping, CSS Color 4.
+// https://www.w3.org/TR/css-color-4/#gamut-mapping
+
+#[cfg(not(any(feature = "std", test)))]
+use crate::math::FloatExt;
+use crate::{Color, ColorSpace};
+
+// Just noticeable difference and precision of the chroma search, in deltaEOK
+const JND: f32 = 0.02;
+const EPSILON: f32 = 0.0001;
+
+// Precision of the gamut geometry queries, and more OKLCH chroma than the
+// gamuts have
+const PRECISION: f32 = 1e-6;
+const MAX_CHROMA: f32 = 0.5;
+
+// Allowance for the rounding error of conversions
+const TOLERANCE: f32 = 1e-5;
+
+/// RGB gamut, the target of [`Color::to_gamut()`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Gamut {
+    #[default]
+    Srgb,
+    DisplayP3,
+    Rec2020,
+}
+
+/// Gamut mapping method of [`Color::to_gamut()`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum GamutMapping {
+    /// The CSS Color 4 algorithm: reduce OKLCH chroma, keeping lightness
+    /// and hue, until clipping the color is less than a just noticeable
+    /// difference away.
+    #[default]
+    Css,
+    /// Clamp the red, green and blue of the gamut, which can shift the hue
+    /// and lightness.
+    Clip,
+    /// Reduce OKLCH chroma until the color is in gamut, keeping lightness
+    /// and hue.
+    Chroma,
+}
+
+impl Gamut {
+    /// The color is in this gamut, allowing for the rounding error of
+    /// conversions.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use csscolorparser::{Color, Gamut};
+    ///
+    /// let c = Color::from_display_p3_rgba(0.0, 1.0, 0.0, 1.0);
+    ///
+    /// assert!(!Gamut::Srgb.contains(&c));
+    /// assert!(Gamut::DisplayP3.contains(&c));
+    /// assert!(Gamut::Rec2020.contains(&c));
+    /// ```
+    pub fn contains(self, c: &Color) -> bool {
+        let [r, g, b, _] = self.space().components(c);
+        [r, g, b]
+            .iter()
+            .all(|t| (-TOLERANCE..=1.0 + TOLERANCE).contains(t))
+    }
+
+    /// The maximum OKLCH chroma in this gamut at lightness `l` and hue `h`
+    /// in radians.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use csscolorparser::{Color, Gamut};
+    ///
+    /// let h = 30f32.to_radians();
+    /// let c = Gamut::Srgb.max_chroma(0.8, h);
+    ///
+    /// assert!(Color::from_oklcha(0.8, c, h, 1.0).is_in_gamut());
+    /// assert!(!Color::from_oklcha(0.8, c + 0.001, h, 1.0).is_in_gamut());
+    /// assert!(Gamut::DisplayP3.max_chroma(0.8, h) > c);
+    /// ```
+    pub fn max_chroma(self, l: f32, h: f32) -> f32 {
+        if l <= 0.0 || l >= 1.0 {
+            return 0.0;
+        }
+        let in_gamut = |c: f32| self.contains(&Color::from_oklcha(l, c, h, 1.0));
+        let mut max = MAX_CHROMA;
+        while in_gamut(max) {
+            max *= 2.0;
+        }
+        let mut min = 0.0;
+        while max - min > PRECISION {
+            let c = (min + max) / 2.0;
+            if in_gamut(c) {
+                min = c;
+            } else {
+                max = c;
+            }
+        }
+        min
+    }
+
+    /// The `[l, c]` of the color with the most OKLCH chroma in this gamut at
+    /// hue `h` in radians.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use csscolorparser::{Color, Gamut};
+    ///
+    /// // The cusp of the hue of red is red
+    /// let [l, c, h, _] = Color::new(1.0, 0.0, 0.0, 1.0).to_oklcha();
+    /// let cusp = Gamut::Srgb.cusp(h);
+    ///
+    /// assert!((cusp[0] - l).abs() < 1e-3);
+    /// assert!((cusp[1] - c).abs() < 1e-3);
+    /// ```
+    pub fn cusp(self, h: f32) -> [f32; 2] {
+        // Golden section search, the maximum chroma increases with
+        // lightness up to the cusp and then decreases.
+        let ratio = (5f32.sqrt() - 1.0) / 2.0;
+        let (mut a, mut b) = (0.0, 1.0);
+        let mut l1 = b - ratio * (b - a);
+        let mut l2 = a + ratio * (b - a);
+        let mut c1 = self.max_chroma(l1, h);
+        let mut c2 = self.max_chroma(l2, h);
+        while b - a > PRECISION {
+            if c1 < c2 {
+                a = l1;
+                l1 = l2;
+                c1 = c2;
+                l2 = a + ratio * (b - a);
+                c2 = self.max_chroma(l2, h);
+            } else {
+                b = l2;
+                l2 = l1;
+                c2 = c1;
+                l1 = b - ratio * (b - a);
+                c1 = self.max_chroma(l1, h);
+            }
+        }
+        let l = (a + b) / 2.0;
+        let mut cusp = [l, self.max_chroma(l, h)];
+
+        // The slice of the gamut at the hue of a primary or secondary color
+        // can reach it in a single point, which the search misses
+        for rgb in [
+            [1.0, 0.0, 0.0],
+            [1.0, 1.0, 0.0],
+            [0.0, 1.0, 0.0],
+            [0.0, 1.0, 1.0],
+            [0.0, 0.0, 1.0],
+            [1.0, 0.0, 1.0],
+        ] {
+            let [l, c, hue, _] = self
+                .space()
+                .to_color([rgb[0], rgb[1], rgb[2], 1.0])
+                .to_oklcha();
+            let dh = hue - h;
+            if c > cusp[1] && dh.sin().abs() < 1e-4 && dh.cos() > 0.0 {
+                cusp = [l, c];
+            }
+        }
+        cusp
+    }
+
+    fn space(self) -> ColorSpace {
+        match self {
+            Self::Srgb => ColorSpace::Srgb,
+            Self::DisplayP3 => ColorSpace::DisplayP3,
+            Self::Rec2020 => ColorSpace::Rec2020,
+        }
+    }
+
+    fn clip(self, c: &Color) -> Color {
+        let [r, g, b, a] = self.space().components(c);
+        let rgb = [r, g, b].map(|t| t.clamp(0.0, 1.0));
+        self.space().to_color([rgb[0], rgb[1], rgb[2], a])
+    }
+}
+
+pub(crate) fn map(c: &Color, gamut: Gamut, method: GamutMapping) -> Color {
+    if gamut.contains(c) {
+        return c.clone();
+    }
+    if method == GamutMapping::Clip {
+        return gamut.clip(c);
+    }
+
+    let [l, chroma, h, alpha] = c.to_oklcha();
+    if l >= 1.0 {
+        return Color::new(1.0, 1.0, 1.0, alpha);
+    }
+    if l <= 0.0 {
+        return Color::new(0.0, 0.0, 0.0, alpha);
+    }
+
+    let with_chroma = |chroma: f32| Color::from_oklcha(l, chroma, h, alpha);
+
+    if method == GamutMapping::Chroma {
+        let (mut min, mut max) = (0.0, chroma);
+        while max - min > EPSILON {
+            let chroma = (min + max) / 2.0;
+            if gamut.contains(&with_chroma(chroma)) {
+                min = chroma;
+            } else {
+                max = chroma;
+            }
+        }
+        // Clip the remaining rounding error
+        return gamut.clip(&with_chroma(min));
+    }
+
+    let mut clipped = gamut.clip(c);
+    if delta_eok(&clipped, c) < JND {
+        return clipped;
+    }
+
+    let (mut min, mut max) = (0.0, chroma);
+    let mut min_in_gamut = true;
+    while max - min > EPSILON {
+        let chroma = (min + max) / 2.0;
+        let current = with_chroma(chroma);
+        if min_in_gamut && gamut.contains(&current) {
+            min = chroma;
+            continue;
+        }
+        clipped = gamut.clip(&current);
+        let e = delta_eok(&clipped, &current);
+        if e < JND {
+            if JND - e < EPSILON {
+                return clipped;
+            }
+            min_in_gamut = false;
+            min = chroma;
+        } else {
+            max = chroma;
+        }
+    }
+    clipped
+}
+
+fn delta_eok(a: &Color, b: &Color) -> f32 {
+    let [l1, a1, b1, _] = a.to_oklaba();
+    let [l2, a2, b2, _] = b.to_oklaba();
+    let (dl, da, db) = (l1 - l2, a1 - a2, b1 - b2);
+    (dl * dl + da * da + db * db).sqrt()
+}
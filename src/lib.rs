@@ -15,7 +15,16 @@
 //! * `hwb()`
 //! * `lab()`
 //! * `lch()`
+//! * `oklab()`
+//! * `oklch()`
+//! * `color()` with `srgb`, `srgb-linear`, `display-p3`, `a98-rgb`, `prophoto-rgb`, `rec2020`, `xyz`, `xyz-d50` and `xyz-d65`
 //! * `hwba()`, `hsv()`, `hsva()` - not in CSS standard.
+//! * [Missing components](https://www.w3.org/TR/css-color-4/#missing) using the `none` keyword
+//! * [`color-mix()`](https://www.w3.org/TR/css-color-5/#color-mix)
+//! * [Math functions](https://www.w3.org/TR/css-values-4/#math) such as `calc()`, `min()`, `max()`, `clamp()` and `round()` in color components
+//! * [Relative color syntax](https://www.w3.org/TR/css-color-5/#relative-colors), e.g. `rgb(from <color> r g b)`
+//! * [`currentcolor`](https://www.w3.org/TR/css-color-4/#currentcolor-color), [system colors](https://www.w3.org/TR/css-color-4/#css-system-colors) and [`light-dark()`](https://www.w3.org/TR/css-color-5/#light-dark) with [`parse_with_context()`]
+//! * [`var()`](https://www.w3.org/TR/css-variables-1/#using-variables) references with [`parse_with_vars()`]
 //!
 //! ### Example Color Format
 //!
@@ -47,6 +56,13 @@
 //! hwb(480deg 0% 0% / 100%)
 //! hsv(120,100%,100%)
 //! hsv(120deg 100% 100% / 100%)
+//! color(srgb 0 1 0)
+//! color(display-p3 0.458 0.986 0.298)
+//! rgb(from lime r g b)
+//! hsl(from #f00 120deg s l)
+//! color-mix(in srgb, lime 50%, #0f0)
+//! rgb(calc(255 * 0.5) 0 0)
+//! hsl(from red calc(h + 180) s l)
 //! ```
 //! </details>
 //!
@@ -88,25 +104,79 @@
 //! # }
 //! ```
 //!
-//! ## Default Feature
+//! Keeping the color space it is written in with [`ParsedColor`].
 //!
+//! ```rust
+//! use csscolorparser::ParsedColor;
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//!
+//! let c = "oklch(0.9 0.4 150)".parse::<ParsedColor>()?;
+//!
+//! assert_eq!(c.to_string(), "oklch(0.9 0.4 150)");
+//! assert_eq!(c.to_color().to_rgba8(), [0, 255, 0, 255]);
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! ## Default Features
+//!
+//! * `std`: Uses the standard library. Without it the crate is `no_std` and requires the `libm` feature.
 //! * `named-colors`: Enables parsing from [named colors](https://www.w3.org/TR/css-color-4/#named-colors). Requires [`phf`](https://crates.io/crates/phf).
 //!
 //! ## Optional Features
 //!
-//! * `lab`: Enables parsing `lab()` and `lch()` color format.
+//! * `alloc`: Enables the APIs that return a `String`, such as [`Color::to_hex_string()`] and [`parse_detailed()`], without `std`.
+//! * `libm`: Uses [`libm`](https://crates.io/crates/libm) for float math, required without `std`.
+//! * `x11`: Enables [X11 color names and `XParseColor` syntaxes](Parser::x11). Requires [`phf`](https://crates.io/crates/phf).
+//! * `lab`: Does nothing, `lab()` and `lch()` are always supported. Kept for compatibility.
 //! * `rust-rgb`: Enables converting from [`rgb`](https://crates.io/crates/rgb) crate types into `Color`.
 //! * `cint`: Enables converting [`cint`](https://crates.io/crates/cint) crate types to and from `Color`.
 //! * `serde`: Enables serializing (into HEX string) and deserializing (from any supported string color format) using [`serde`](https://serde.rs/) framework.
 
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
+#[cfg(not(any(feature = "std", feature = "libm")))]
+compile_error!("csscolorparser requires either the `std` or the `libm` feature");
+
+mod adaptation;
 mod color;
+mod colorspace;
+mod context;
+mod contrast;
+mod delta_e;
+mod dialect;
+mod gamut;
+mod parsed;
 mod parser;
+mod rgbspace;
+
+#[cfg(not(any(feature = "std", test)))]
+mod math;
 
 #[cfg(feature = "cint")]
 mod cint;
 
+pub use adaptation::{ChromaticAdaptation, WhitePoint};
 pub use color::Color;
-pub use parser::{parse, ParseColorError};
+pub use colorspace::{ColorSpace, HueInterpolation};
+pub use context::{ColorScheme, Context, SystemColor};
+pub use contrast::{TextSize, WcagLevel};
+pub use delta_e::DeltaE;
+pub use dialect::{parse_dialect, Dialect};
+pub use gamut::{Gamut, GamutMapping};
+pub use parsed::{Notation, ParsedColor};
+pub use parser::{
+    parse, parse_html_legacy, parse_with_context, Expected, NameTable, ParseColorError, Parser,
+};
+#[cfg(feature = "alloc")]
+pub use parser::{parse_detailed, parse_with_vars, ParseError, VarError, VarResolver};
+pub use rgbspace::{RgbSpace, TransferFunction};
 
 #[cfg(feature = "named-colors")]
 pub use parser::NAMED_COLORS;
+
+#[cfg(feature = "x11")]
+pub use parser::X11_COLORS;
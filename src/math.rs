@@ -0,0 +1,113 @@
+// The float functions of `std` that are not in `core`, implemented with
+// `libm`. Inherent methods take precedence, so this is only used without
+// `std`.
+
+pub(crate) trait FloatExt {
+    fn acos(self) -> Self;
+    fn asin(self) -> Self;
+    fn atan(self) -> Self;
+    fn atan2(self, other: Self) -> Self;
+    fn cbrt(self) -> Self;
+    fn ceil(self) -> Self;
+    fn cos(self) -> Self;
+    fn exp(self) -> Self;
+    fn floor(self) -> Self;
+    fn hypot(self, other: Self) -> Self;
+    fn ln(self) -> Self;
+    fn log(self, base: Self) -> Self;
+    fn powf(self, n: Self) -> Self;
+    fn powi(self, n: i32) -> Self;
+    fn rem_euclid(self, rhs: Self) -> Self;
+    fn round(self) -> Self;
+    fn sin(self) -> Self;
+    fn sqrt(self) -> Self;
+    fn tan(self) -> Self;
+    fn trunc(self) -> Self;
+}
+
+impl FloatExt for f32 {
+    fn acos(self) -> Self {
+        libm::acosf(self)
+    }
+
+    fn asin(self) -> Self {
+        libm::asinf(self)
+    }
+
+    fn atan(self) -> Self {
+        libm::atanf(self)
+    }
+
+    fn atan2(self, other: Self) -> Self {
+        libm::atan2f(self, other)
+    }
+
+    fn cbrt(self) -> Self {
+        libm::cbrtf(self)
+    }
+
+    fn ceil(self) -> Self {
+        libm::ceilf(self)
+    }
+
+    fn cos(self) -> Self {
+        libm::cosf(self)
+    }
+
+    fn exp(self) -> Self {
+        libm::expf(self)
+    }
+
+    fn floor(self) -> Self {
+        libm::floorf(self)
+    }
+
+    fn hypot(self, other: Self) -> Self {
+        libm::hypotf(self, other)
+    }
+
+    fn ln(self) -> Self {
+        libm::logf(self)
+    }
+
+    fn log(self, base: Self) -> Self {
+        libm::logf(self) / libm::logf(base)
+    }
+
+    fn powf(self, n: Self) -> Self {
+        libm::powf(self, n)
+    }
+
+    fn powi(self, n: i32) -> Self {
+        libm::powf(self, n as f32)
+    }
+
+    fn rem_euclid(self, rhs: Self) -> Self {
+        let r = self % rhs;
+        if r < 0.0 {
+            r + rhs.abs()
+        } else {
+            r
+        }
+    }
+
+    fn round(self) -> Self {
+        libm::roundf(self)
+    }
+
+    fn sin(self) -> Self {
+        libm::sinf(self)
+    }
+
+    fn sqrt(self) -> Self {
+        libm::sqrtf(self)
+    }
+
+    fn tan(self) -> Self {
+        libm::tanf(self)
+    }
+
+    fn trunc(self) -> Self {
+        libm::truncf(self)
+    }
+}
@@ -0,0 +1,353 @@
+// Colors in the color space and notation they are written in.
+
+use core::fmt::{self, Write};
+use core::str::FromStr;
+
+use crate::{Color, ColorSpace, ParseColorError, Parser};
+
+/// How a [`ParsedColor`] is written
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Notation {
+    /// Hex color, e.g. `#ff8000`.
+    Hex,
+    /// Named color or keyword, e.g. `orange`, `transparent` or
+    /// `currentcolor`.
+    Named,
+    /// The function of the color space, e.g. `rgb()`, `hsl()` or `oklch()`.
+    Function,
+    /// `color()` with a predefined color space, e.g. `color(display-p3 1 0 0)`.
+    ColorFunction,
+}
+
+/// Color in the color space and notation it is written in, see
+/// [`Parser::parse_preserved()`]
+///
+/// Unlike [`Color`] it keeps out of range components, e.g. the red of
+/// `rgb(300 0 0)`, and missing components (`none`). [`ParsedColor::to_color()`]
+/// converts it, and [`Display`](fmt::Display) writes it back in its notation.
+/// `rgba()`, `hsla()`, `hwba()` and `hsva()` are written as `rgb()`, `hsl()`,
+/// `hwb()` and `hsv()`, named colors are written as their keyword in
+/// lowercase.
+///
+/// # Examples
+///
+/// ```
+/// use csscolorparser::{ColorSpace, Notation, ParsedColor};
+///
+/// let c: ParsedColor = "oklch(0.9 0.4 150)".parse().unwrap();
+///
+/// assert_eq!(c.space(), ColorSpace::Oklch);
+/// assert_eq!(c.notation(), Notation::Function);
+/// assert_eq!(c.components(), [Some(0.9), Some(0.4), Some(150.0)]);
+/// assert_eq!(c.to_string(), "oklch(0.9 0.4 150)");
+///
+/// let c: ParsedColor = "rgb(300 0 none / 50%)".parse().unwrap();
+///
+/// assert_eq!(c.to_string(), "rgb(300 0 none / 0.5)");
+/// assert_eq!(c.to_color().to_array(), [300.0 / 255.0, 0.0, 0.0, 0.5]);
+///
+/// let c: ParsedColor = "RebeccaPurple".parse().unwrap();
+///
+/// assert_eq!(c.name(), Some("rebeccapurple"));
+/// assert_eq!(c.to_string(), "rebeccapurple");
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct ParsedColor {
+    space: ColorSpace,
+    // Hue in degrees, missing components are NaN
+    values: [f32; 4],
+    notation: Notation,
+    name: Option<Keyword>,
+}
+
+impl ParsedColor {
+    /// Color with the components of `space` and alpha, `None` is a missing
+    /// component. The hue of polar color spaces is in degrees, the
+    /// saturation, lightness, whiteness, blackness and value of `hsl()`,
+    /// `hwb()` and `hsv()` are in the range [0..1].
+    pub fn new(
+        space: ColorSpace,
+        components: [Option<f32>; 3],
+        alpha: Option<f32>,
+        notation: Notation,
+    ) -> Self {
+        let [a, b, c] = components.map(|t| t.unwrap_or(f32::NAN));
+        Self {
+            space,
+            values: [a, b, c, alpha.unwrap_or(f32::NAN)],
+            notation,
+            name: None,
+        }
+    }
+
+    pub(crate) fn from_values(notation: Notation, (space, values): (ColorSpace, [f32; 4])) -> Self {
+        Self {
+            space,
+            values,
+            notation,
+            name: None,
+        }
+    }
+
+    pub(crate) fn with_name(mut self, name: &str) -> Self {
+        self.name = Keyword::new(name);
+        self
+    }
+
+    // The result of `color-mix()` is written like CSS serializes it.
+    pub(crate) fn mixed(space: ColorSpace, values: [f32; 4]) -> Self {
+        let notation = match space {
+            ColorSpace::Srgb
+            | ColorSpace::SrgbLinear
+            | ColorSpace::DisplayP3
+            | ColorSpace::A98Rgb
+            | ColorSpace::ProphotoRgb
+            | ColorSpace::Rec2020
+            | ColorSpace::XyzD50
+            | ColorSpace::XyzD65 => Notation::ColorFunction,
+            _ => Notation::Function,
+        };
+        Self::from_values(notation, (space, values))
+    }
+
+    /// Parse CSS color string with [`Parser::new()`]
+    pub fn parse(s: &str) -> Result<Self, ParseColorError> {
+        Parser::new().parse_preserved(s)
+    }
+
+    /// The color space of the components
+    pub fn space(&self) -> ColorSpace {
+        self.space
+    }
+
+    /// How the color is written
+    pub fn notation(&self) -> Notation {
+        self.notation
+    }
+
+    /// The keyword of a named color in lowercase, e.g. `orange` or
+    /// `currentcolor`. `None` for other notations and for keywords longer
+    /// than 32 bytes, which are written as hex.
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_ref().map(Keyword::as_str)
+    }
+
+    /// The components as written, `None` if missing
+    pub fn components(&self) -> [Option<f32>; 3] {
+        let [a, b, c, _] = self.values;
+        [a, b, c].map(component)
+    }
+
+    /// Alpha as written, `None` if missing
+    pub fn alpha(&self) -> Option<f32> {
+        component(self.values[3])
+    }
+
+    /// Convert to [`Color`] like [`parse()`](crate::parse) does: missing
+    /// components are zero and alpha is clamped to the range [0..1]. Colors
+    /// out of the sRGB gamut are not clamped.
+    pub fn to_color(&self) -> Color {
+        let (space, values) = self.resolved();
+        space.to_color(values)
+    }
+
+    // The components with alpha clamped like in CSS, missing components are
+    // kept.
+    pub(crate) fn resolved(&self) -> (ColorSpace, [f32; 4]) {
+        let mut values = self.values;
+        values[3] = values[3].clamp(0.0, 1.0);
+        (self.space, values)
+    }
+
+    // The name of the function the color is written with, and whether it
+    // is a color space of `color()`.
+    fn function(&self) -> (&'static str, bool) {
+        let name = match self.space {
+            ColorSpace::Srgb if self.notation != Notation::ColorFunction => return ("rgb", false),
+            ColorSpace::Srgb => "srgb",
+            ColorSpace::SrgbLinear => "srgb-linear",
+            ColorSpace::DisplayP3 => "display-p3",
+            ColorSpace::A98Rgb => "a98-rgb",
+            ColorSpace::ProphotoRgb => "prophoto-rgb",
+            ColorSpace::Rec2020 => "rec2020",
+            ColorSpace::XyzD50 => "xyz-d50",
+            ColorSpace::XyzD65 => "xyz-d65",
+            ColorSpace::Lab => return ("lab", false),
+            ColorSpace::Lch => return ("lch", false),
+            ColorSpace::Oklab => return ("oklab", false),
+            ColorSpace::Oklch => return ("oklch", false),
+            ColorSpace::Hsl => return ("hsl", false),
+            ColorSpace::Hwb => return ("hwb", false),
+            ColorSpace::Hsv => return ("hsv", false),
+        };
+        (name, true)
+    }
+}
+
+// Keyword of a named color, stored inline to keep `ParsedColor` `Copy`.
+#[derive(Clone, Copy, PartialEq, Eq)]
+struct Keyword {
+    bytes: [u8; 32],
+    len: usize,
+}
+
+impl Keyword {
+    fn new(s: &str) -> Option<Self> {
+        let mut bytes = [0; 32];
+        bytes.get_mut(..s.len())?.copy_from_slice(s.as_bytes());
+        bytes.make_ascii_lowercase();
+        Some(Self {
+            bytes,
+            len: s.len(),
+        })
+    }
+
+    fn as_str(&self) -> &str {
+        // Lowercasing ASCII keeps UTF-8 valid
+        core::str::from_utf8(&self.bytes[..self.len]).unwrap_or_default()
+    }
+}
+
+impl fmt::Debug for Keyword {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.as_str().fmt(f)
+    }
+}
+
+fn component(t: f32) -> Option<f32> {
+    if t.is_nan() {
+        None
+    } else {
+        Some(t)
+    }
+}
+
+// Missing components are equal to each other.
+impl PartialEq for ParsedColor {
+    fn eq(&self, other: &Self) -> bool {
+        self.space == other.space
+            && self.notation == other.notation
+            && self.name == other.name
+            && self.components() == other.components()
+            && self.alpha() == other.alpha()
+    }
+}
+
+impl fmt::Display for ParsedColor {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if let Some(name) = self.name() {
+            return f.write_str(name);
+        }
+        if let Notation::Hex | Notation::Named = self.notation {
+            let [r, g, b, a] = self.to_color().to_rgba8();
+            write!(f, "#{:02x}{:02x}{:02x}", r, g, b)?;
+            if a < 255 {
+                write!(f, "{:02x}", a)?;
+            }
+            return Ok(());
+        }
+
+        let (function, predefined) = self.function();
+        if predefined {
+            write!(f, "color({} ", function)?;
+        } else {
+            write!(f, "{}(", function)?;
+        }
+
+        // The scale of the components in the notation
+        let (scale, percent) = match function {
+            "rgb" => (255.0, false),
+            "hsl" | "hwb" | "hsv" => (100.0, true),
+            _ => (1.0, false),
+        };
+        for (i, t) in self.components().iter().enumerate() {
+            if i > 0 {
+                f.write_str(" ")?;
+            }
+            match *t {
+                None => f.write_str("none")?,
+                // The hue is in degrees
+                Some(t) if percent && i == 0 => number(f, t, 1.0)?,
+                Some(t) if percent => {
+                    number(f, t, scale)?;
+                    f.write_str("%")?;
+                }
+                Some(t) => number(f, t, scale)?,
+            }
+        }
+        match self.alpha() {
+            Some(1.0) => {}
+            Some(a) => {
+                f.write_str(" / ")?;
+                number(f, a, 1.0)?;
+            }
+            None => f.write_str(" / none")?,
+        }
+        f.write_str(")")
+    }
+}
+
+// Write `t * scale` with the fewest decimals that parse back to `t` when
+// divided by `scale`, like the parser does, without negative zero.
+fn number(f: &mut fmt::Formatter, t: f32, scale: f32) -> fmt::Result {
+    let x = f64::from(t) * f64::from(scale) + 0.0;
+    for decimals in 0..=45 {
+        let mut buf = Buffer::default();
+        if write!(buf, "{:.*}", decimals, x).is_err() {
+            break;
+        }
+        match buf.as_str().parse::<f32>() {
+            Ok(y) if y / scale == t => return f.write_str(buf.as_str()),
+            _ => {}
+        }
+    }
+    // Not a quotient of `scale`, e.g. the result of a conversion
+    write!(f, "{}", t * scale + 0.0)
+}
+
+// Formats a number without allocating.
+struct Buffer {
+    bytes: [u8; 96],
+    len: usize,
+}
+
+impl Default for Buffer {
+    fn default() -> Self {
+        Self {
+            bytes: [0; 96],
+            len: 0,
+        }
+    }
+}
+
+impl Buffer {
+    fn as_str(&self) -> &str {
+        core::str::from_utf8(&self.bytes[..self.len]).unwrap_or_default()
+    }
+}
+
+impl fmt::Write for Buffer {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let end = self.len + s.len();
+        self.bytes
+            .get_mut(self.len..end)
+            .ok_or(fmt::Error)?
+            .copy_from_slice(s.as_bytes());
+        self.len = end;
+        Ok(())
+    }
+}
+
+impl FromStr for ParsedColor {
+    type Err = ParseColorError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+impl From<ParsedColor> for Color {
+    fn from(c: ParsedColor) -> Self {
+        c.to_color()
+    }
+}
@@ -0,0 +1,482 @@
+// Math functions in color arguments, e.g. `calc(255 * 0.5)`.
+// https://www.w3.org/TR/css-values-4/#math
+
+use core::f32::consts::{E, PI};
+
+use super::tokenizer::{name, Token, Tokenizer};
+#[cfg(not(any(feature = "std", test)))]
+use crate::math::FloatExt;
+
+/// A typed value of a color argument.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum Value {
+    Number(f32),
+    /// Percentage as written, `50%` is `50.0`.
+    Percentage(f32),
+    /// Angle in degrees.
+    Angle(f32),
+}
+
+impl Value {
+    fn map(self, f: impl Fn(f32) -> f32) -> Self {
+        match self {
+            Self::Number(t) => Self::Number(f(t)),
+            Self::Percentage(t) => Self::Percentage(f(t)),
+            Self::Angle(t) => Self::Angle(f(t)),
+        }
+    }
+
+    fn get(self) -> f32 {
+        match self {
+            Self::Number(t) | Self::Percentage(t) | Self::Angle(t) => t,
+        }
+    }
+
+    // Combine two values of the same type.
+    fn zip(self, other: Self, f: impl Fn(f32, f32) -> f32) -> Option<Self> {
+        match (self, other) {
+            (Self::Number(a), Self::Number(b)) => Some(Self::Number(f(a, b))),
+            (Self::Percentage(a), Self::Percentage(b)) => Some(Self::Percentage(f(a, b))),
+            (Self::Angle(a), Self::Angle(b)) => Some(Self::Angle(f(a, b))),
+            _ => None,
+        }
+    }
+
+    fn number(self) -> Option<f32> {
+        match self {
+            Self::Number(t) => Some(t),
+            _ => None,
+        }
+    }
+
+    // Angle in radians, plain numbers are radians too.
+    fn radians(self) -> Option<f32> {
+        match self {
+            Self::Number(t) => Some(t),
+            Self::Angle(t) => Some(t.to_radians()),
+            Self::Percentage(_) => None,
+        }
+    }
+}
+
+/// The value of a number, percentage or dimension token, e.g. `50%` or
+/// `0.25turn`.
+pub(crate) fn dimension(token: Token<'_>) -> Option<Value> {
+    match token {
+        Token::Number(t) => Some(Value::Number(t)),
+        Token::Percentage(t) => Some(Value::Percentage(t)),
+        Token::Dimension(t, unit) => match &*name(unit) {
+            "deg" => Some(Value::Angle(t)),
+            "grad" => Some(Value::Angle(t * 360.0 / 400.0)),
+            "rad" => Some(Value::Angle(t.to_degrees())),
+            "turn" => Some(Value::Angle(t * 360.0)),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+const FUNCTIONS: &[&str] = &[
+    "calc", "min", "max", "clamp", "round", "mod", "rem", "abs", "sign", "sin", "cos", "tan",
+    "asin", "acos", "atan", "atan2", "pow", "sqrt", "hypot", "log", "exp",
+];
+
+/// Whether `name` is a math function, e.g. `calc` or `min`.
+pub(crate) fn is_math_function(name: &str) -> bool {
+    FUNCTIONS.contains(&name)
+}
+
+/// Evaluate a math function. Identifiers other than the math constants are
+/// looked up in `vars`, e.g. the channel keywords of relative color syntax.
+///
+/// Like in CSS, a NaN result becomes `0` and infinite results are clamped to
+/// the largest finite value.
+pub(crate) fn eval(s: &str, vars: &dyn Fn(&str) -> Option<f32>) -> Option<Value> {
+    let mut p = Parser {
+        tokens: Tokenizer::new(s),
+        vars,
+        depth: 0,
+    };
+    let v = p.value()?;
+    if !p.tokens.is_exhausted() {
+        return None;
+    }
+    Some(v.map(|t| {
+        if t.is_nan() {
+            0.0
+        } else {
+            t.clamp(f32::MIN, f32::MAX)
+        }
+    }))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Rounding {
+    Nearest,
+    Up,
+    Down,
+    ToZero,
+}
+
+// Parentheses and math functions are nested at most this many levels deep.
+const MAX_DEPTH: usize = 32;
+
+struct Parser<'a, 'b> {
+    tokens: Tokenizer<'a>,
+    vars: &'b dyn Fn(&str) -> Option<f32>,
+    depth: usize,
+}
+
+impl Parser<'_, '_> {
+    fn expect(&mut self, token: Token<'_>) -> Option<()> {
+        self.tokens.skip_whitespace();
+        if self.tokens.next()? == token {
+            return Some(());
+        }
+        None
+    }
+
+    // sum := product [ WS ('+' | '-') WS product ]*
+    fn sum(&mut self) -> Option<Value> {
+        let mut v = self.product()?;
+        loop {
+            let saved = self.tokens.clone();
+            let ws = self.tokens.skip_whitespace();
+            let op = match self.tokens.peek() {
+                Some(Token::Delim(c @ ('+' | '-'))) => c,
+                _ => {
+                    self.tokens = saved;
+                    return Some(v);
+                }
+            };
+            self.tokens.next();
+            // `+` and `-` must be surrounded by whitespace
+            if !ws || !self.tokens.skip_whitespace() {
+                return None;
+            }
+            let rhs = self.product()?;
+            v = if op == '+' {
+                v.zip(rhs, |a, b| a + b)?
+            } else {
+                v.zip(rhs, |a, b| a - b)?
+            };
+        }
+    }
+
+    // product := value [ ('*' | '/') value ]*
+    fn product(&mut self) -> Option<Value> {
+        let mut v = self.value()?;
+        loop {
+            let saved = self.tokens.clone();
+            self.tokens.skip_whitespace();
+            let op = match self.tokens.peek() {
+                Some(Token::Delim(c @ ('*' | '/'))) => c,
+                _ => {
+                    self.tokens = saved;
+                    return Some(v);
+                }
+            };
+            self.tokens.next();
+            let rhs = self.value()?;
+            v = match (op, v, rhs) {
+                ('*', Value::Number(a), rhs) => rhs.map(|b| a * b),
+                ('*', lhs, Value::Number(b)) => lhs.map(|a| a * b),
+                ('/', lhs, Value::Number(b)) => lhs.map(|a| a / b),
+                _ => return None,
+            };
+        }
+    }
+
+    fn value(&mut self) -> Option<Value> {
+        self.tokens.skip_whitespace();
+        match self.tokens.next()? {
+            Token::Ident(s) => match &*name(s) {
+                "e" => Some(Value::Number(E)),
+                "pi" => Some(Value::Number(PI)),
+                "infinity" => Some(Value::Number(f32::INFINITY)),
+                "-infinity" => Some(Value::Number(f32::NEG_INFINITY)),
+                "nan" => Some(Value::Number(f32::NAN)),
+                s => (self.vars)(s).map(Value::Number),
+            },
+            Token::OpenParen => self.nested(|p| {
+                let v = p.sum()?;
+                p.expect(Token::CloseParen)?;
+                Some(v)
+            }),
+            Token::Function(s) => self.nested(|p| p.function(&name(s))),
+            token => dimension(token),
+        }
+    }
+
+    fn nested(&mut self, f: impl FnOnce(&mut Self) -> Option<Value>) -> Option<Value> {
+        if self.depth == MAX_DEPTH {
+            return None;
+        }
+        self.depth += 1;
+        let v = f(self);
+        self.depth -= 1;
+        v
+    }
+
+    fn function(&mut self, fname: &str) -> Option<Value> {
+        // Optional rounding strategy of `round()`
+        let mut strategy = None;
+        if fname == "round" {
+            let saved = self.tokens.clone();
+            self.tokens.skip_whitespace();
+            if let Some(Token::Ident(s)) = self.tokens.next() {
+                strategy = match &*name(s) {
+                    "nearest" => Some(Rounding::Nearest),
+                    "up" => Some(Rounding::Up),
+                    "down" => Some(Rounding::Down),
+                    "to-zero" => Some(Rounding::ToZero),
+                    _ => None,
+                };
+            }
+            if strategy.is_some() {
+                self.expect(Token::Comma)?;
+            } else {
+                self.tokens = saved;
+            }
+        }
+        let strategy = strategy.unwrap_or(Rounding::Nearest);
+
+        // Variadic functions are folded as their arguments are parsed
+        let first = self.sum()?;
+        let mut acc = if fname == "hypot" {
+            first.map(|t| t * t)
+        } else {
+            first
+        };
+        let mut args = [first; 3];
+        let mut n = 1;
+        loop {
+            self.tokens.skip_whitespace();
+            match self.tokens.next()? {
+                Token::Comma => {
+                    let v = self.sum()?;
+                    acc = match fname {
+                        "min" => acc.zip(v, min)?,
+                        "max" => acc.zip(v, max)?,
+                        "hypot" => acc.zip(v, |x, y| x + y * y)?,
+                        _ if n < args.len() => {
+                            args[n] = v;
+                            n += 1;
+                            acc
+                        }
+                        _ => return None,
+                    };
+                }
+                Token::CloseParen => break,
+                _ => return None,
+            }
+        }
+
+        match (fname, &args[..n]) {
+            ("calc", &[a]) => Some(a),
+            ("min", _) | ("max", _) => Some(acc),
+            ("clamp", &[lo, v, hi]) => lo.zip(v.zip(hi, min)?, max),
+            ("round", &[a]) => a
+                .number()
+                .map(|_| a)
+                .and_then(|a| round(strategy, a, Value::Number(1.0))),
+            ("round", &[a, b]) => round(strategy, a, b),
+            ("mod", &[a, b]) => a.zip(b, |x, y| x - y * (x / y).floor()),
+            ("rem", &[a, b]) => a.zip(b, |x, y| x % y),
+            ("abs", &[a]) => Some(a.map(f32::abs)),
+            ("sign", &[a]) => {
+                let t = a.get();
+                Some(Value::Number(if t == 0.0 || t.is_nan() {
+                    t
+                } else {
+                    t.signum()
+                }))
+            }
+            ("sin", &[a]) => a.radians().map(|t| Value::Number(t.sin())),
+            ("cos", &[a]) => a.radians().map(|t| Value::Number(t.cos())),
+            ("tan", &[a]) => a.radians().map(|t| Value::Number(t.tan())),
+            ("asin", &[a]) => a.number().map(|t| Value::Angle(t.asin().to_degrees())),
+            ("acos", &[a]) => a.number().map(|t| Value::Angle(t.acos().to_degrees())),
+            ("atan", &[a]) => a.number().map(|t| Value::Angle(t.atan().to_degrees())),
+            ("atan2", &[a, b]) => a
+                .zip(b, |y, x| y.atan2(x).to_degrees())
+                .map(|v| Value::Angle(v.get())),
+            ("pow", &[a, b]) => Some(Value::Number(a.number()?.powf(b.number()?))),
+            ("sqrt", &[a]) => a.number().map(|t| Value::Number(t.sqrt())),
+            ("hypot", _) => Some(acc.map(f32::sqrt)),
+            ("log", &[a]) => a.number().map(|t| Value::Number(t.ln())),
+            ("log", &[a, b]) => Some(Value::Number(a.number()?.log(b.number()?))),
+            ("exp", &[a]) => a.number().map(|t| Value::Number(t.exp())),
+            _ => None,
+        }
+    }
+}
+
+// `f32::min()` and `f32::max()` ignore NaN, CSS propagates it.
+fn min(x: f32, y: f32) -> f32 {
+    if x.is_nan() || y.is_nan() {
+        f32::NAN
+    } else {
+        x.min(y)
+    }
+}
+
+fn max(x: f32, y: f32) -> f32 {
+    if x.is_nan() || y.is_nan() {
+        f32::NAN
+    } else {
+        x.max(y)
+    }
+}
+
+fn round(strategy: Rounding, a: Value, b: Value) -> Option<Value> {
+    a.zip(b, |x, y| {
+        if y == 0.0 {
+            return f32::NAN;
+        }
+        let t = x / y;
+        let t = match strategy {
+            Rounding::Up => t.ceil(),
+            Rounding::Down => t.floor(),
+            Rounding::ToZero => t.trunc(),
+            // Ties round towards positive infinity
+            Rounding::Nearest => (t + 0.5).floor(),
+        };
+        t * y
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn no_vars(_: &str) -> Option<f32> {
+        None
+    }
+
+    #[test]
+    fn test_eval() {
+        let test_data = [
+            ("calc(1)", Value::Number(1.0)),
+            ("calc(255 * 0.5)", Value::Number(127.5)),
+            ("calc(1 + 2 * 3)", Value::Number(7.0)),
+            ("calc((1 + 2) * 3)", Value::Number(9.0)),
+            ("calc(10 - 4 / 2)", Value::Number(8.0)),
+            ("calc(-1 - -2)", Value::Number(1.0)),
+            ("calc(1e2 + 1)", Value::Number(101.0)),
+            ("calc(50% + 10%)", Value::Percentage(60.0)),
+            ("calc(50% * 2)", Value::Percentage(100.0)),
+            ("calc(2 * 50%)", Value::Percentage(100.0)),
+            ("calc(120deg + 30deg)", Value::Angle(150.0)),
+            ("calc(0.5turn - 90deg)", Value::Angle(90.0)),
+            ("calc(calc(1 + 1) * 2)", Value::Number(4.0)),
+            ("min(1, 2, 3)", Value::Number(1.0)),
+            ("max(10%, 20%)", Value::Percentage(20.0)),
+            ("clamp(0, 300, 255)", Value::Number(255.0)),
+            ("clamp(0, -5, 255)", Value::Number(0.0)),
+            ("round(2.5)", Value::Number(3.0)),
+            ("round(-2.5)", Value::Number(-2.0)),
+            ("round(up, 2.1, 1)", Value::Number(3.0)),
+            ("round(down, 2.9, 1)", Value::Number(2.0)),
+            ("round(to-zero, -2.9, 1)", Value::Number(-2.0)),
+            ("round(nearest, 17, 5)", Value::Number(15.0)),
+            ("round(47deg, 10deg)", Value::Angle(50.0)),
+            ("mod(-5, 3)", Value::Number(1.0)),
+            ("rem(-5, 3)", Value::Number(-2.0)),
+            ("mod(400deg, 360deg)", Value::Angle(40.0)),
+            ("abs(-5%)", Value::Percentage(5.0)),
+            ("sign(-20deg)", Value::Number(-1.0)),
+            ("sign(0)", Value::Number(0.0)),
+            ("sin(90deg)", Value::Number(1.0)),
+            ("cos(0)", Value::Number(1.0)),
+            ("tan(0deg)", Value::Number(0.0)),
+            ("asin(1)", Value::Angle(90.0)),
+            ("acos(1)", Value::Angle(0.0)),
+            ("atan(1)", Value::Angle(45.0)),
+            ("atan2(1, 1)", Value::Angle(45.0)),
+            ("pow(2, 10)", Value::Number(1024.0)),
+            ("sqrt(16)", Value::Number(4.0)),
+            ("hypot(3, 4)", Value::Number(5.0)),
+            ("log(1)", Value::Number(0.0)),
+            ("log(8, 2)", Value::Number(3.0)),
+            ("exp(0)", Value::Number(1.0)),
+            ("calc(e)", Value::Number(E)),
+            ("calc(pi)", Value::Number(PI)),
+            ("calc(infinity)", Value::Number(f32::MAX)),
+            ("calc(-infinity)", Value::Number(f32::MIN)),
+            ("calc(1 / infinity)", Value::Number(0.0)),
+            ("calc(nan)", Value::Number(0.0)),
+            ("round(1, 0)", Value::Number(0.0)),
+            ("calc( 1  +  2 )", Value::Number(3.0)),
+        ];
+        for (s, expected) in test_data {
+            assert_eq!(eval(s, &no_vars), Some(expected), "{}", s);
+        }
+    }
+
+    #[test]
+    fn test_eval_vars() {
+        let vars = |name: &str| match name {
+            "h" => Some(200.0),
+            "l" => Some(0.5),
+            _ => None,
+        };
+        assert_eq!(eval("calc(h + 180)", &vars), Some(Value::Number(380.0)));
+        assert_eq!(eval("calc(l * 2)", &vars), Some(Value::Number(1.0)));
+        assert_eq!(eval("calc(x + 1)", &vars), None);
+    }
+
+    #[test]
+    fn test_eval_invalid() {
+        let test_data = [
+            "calc()",
+            "calc(1",
+            "calc(1 +)",
+            "calc(1+2)",
+            "calc(1 -2)",
+            "calc(1 + 2))",
+            "calc(1 2)",
+            "calc(1 + 10%)",
+            "calc(1deg + 10%)",
+            "calc(10% * 10%)",
+            "calc(1 / 10%)",
+            "calc(foo)",
+            "calc(1, 2)",
+            "min()",
+            "min(1, 10%)",
+            "clamp(1, 2)",
+            "round(5deg)",
+            "round(sideways, 5, 1)",
+            "sin(10%)",
+            "asin(1deg)",
+            "pow(2deg, 2)",
+            "calc(inf)",
+            "calc(1 # 2)",
+            "foo(1)",
+        ];
+        for s in test_data {
+            assert_eq!(eval(s, &no_vars), None, "{}", s);
+        }
+    }
+
+    #[test]
+    fn test_eval_depth() {
+        let nested = |f: &str, n| f.repeat(n) + "1" + &")".repeat(n);
+        assert_eq!(
+            eval(&nested("calc(", 32), &no_vars),
+            Some(Value::Number(1.0))
+        );
+        assert_eq!(eval(&nested("calc(", 33), &no_vars), None);
+        assert_eq!(eval(&nested("calc(", 100_000), &no_vars), None);
+        let parens = |n| String::from("calc") + &nested("(", n);
+        assert_eq!(eval(&parens(32), &no_vars), Some(Value::Number(1.0)));
+        assert_eq!(eval(&parens(33), &no_vars), None);
+    }
+
+    #[test]
+    fn test_is_math_function() {
+        assert!(is_math_function("calc"));
+        assert!(is_math_function("min"));
+        assert!(!is_math_function("rgb"));
+    }
+}
@@ -0,0 +1,100 @@
+// https://html.spec.whatwg.org/multipage/common-microsyntaxes.html#rules-for-parsing-a-legacy-colour-value
+
+use super::ParseColorError;
+use crate::Color;
+
+#[cfg(feature = "named-colors")]
+use super::NAMED_COLORS;
+
+const MAX_LEN: usize = 128;
+
+/// Parse HTML attribute color, e.g. `bgcolor`, with the WHATWG
+/// [rules for parsing a legacy colour value](https://html.spec.whatwg.org/multipage/common-microsyntaxes.html#rules-for-parsing-a-legacy-colour-value)
+///
+/// Named colors and `#rgb` are parsed like in CSS. Anything else is read as
+/// a hex color the way browsers do: characters that are not hex digits
+/// become `0`, and the digits are split into three components, so that
+/// every string except `""` and `transparent` is a color. Whitespace is
+/// black.
+///
+/// # Examples
+///
+/// ```
+/// use csscolorparser::parse_html_legacy;
+///
+/// assert_eq!(parse_html_legacy("chucknorris").unwrap().to_rgba8(), [192, 0, 0, 255]);
+/// assert_eq!(parse_html_legacy("#ff8000").unwrap().to_rgba8(), [255, 128, 0, 255]);
+/// assert_eq!(parse_html_legacy("ff8000").unwrap().to_rgba8(), [255, 128, 0, 255]);
+/// assert_eq!(parse_html_legacy("   ").unwrap().to_rgba8(), [0, 0, 0, 255]);
+/// assert!(parse_html_legacy("transparent").is_err());
+///
+/// // Not CSS
+/// assert_eq!(parse_html_legacy("rgb(255, 0, 0)").unwrap().to_rgba8(), [0, 85, 0, 255]);
+/// ```
+pub fn parse_html_legacy(s: &str) -> Result<Color, ParseColorError> {
+    // Only the empty string is an error, whitespace is trimmed after
+    if s.is_empty() {
+        return Err(ParseColorError::InvalidUnknown);
+    }
+    let s = s.trim_matches(|c| matches!(c, '\t' | '\n' | '\x0C' | '\r' | ' '));
+
+    if s.eq_ignore_ascii_case("transparent") {
+        return Err(ParseColorError::InvalidUnknown);
+    }
+
+    #[cfg(feature = "named-colors")]
+    if let Some([r, g, b]) = named_color(s) {
+        return Ok(Color::from_rgba8(r, g, b, 255));
+    }
+
+    // #rgb
+    if let [b'#', digits @ ..] = s.as_bytes() {
+        if digits.len() == 3 && digits.iter().all(u8::is_ascii_hexdigit) {
+            let [r, g, b] = [0, 1, 2].map(|i| hex_digit(digits[i]) * 17);
+            return Ok(Color::from_rgba8(r, g, b, 255));
+        }
+    }
+
+    // Code points above U+FFFF count as two, they become "00"
+    let mut buf = [b'0'; MAX_LEN];
+    let mut len = 0;
+    for c in s.chars() {
+        if len == MAX_LEN {
+            break;
+        }
+        if c.is_ascii_hexdigit() || (c == '#' && len == 0) {
+            buf[len] = c as u8;
+        }
+        len += if (c as u32) > 0xFFFF { 2 } else { 1 };
+    }
+    let len = len.min(MAX_LEN);
+    let digits = match &buf[..len] {
+        [b'#', rest @ ..] => rest,
+        digits => digits,
+    };
+
+    // Three components, at most 8 digits, without the leading zeros they
+    // all have, and the first two digits of those
+    let n = digits.len().div_ceil(3).max(1);
+    let digit = |i: usize, j: usize| digits.get(i * n + j).map_or(0, |&d| hex_digit(d));
+    let mut start = n.saturating_sub(8);
+    while n - start > 2 && (0..3).all(|i| digit(i, start) == 0) {
+        start += 1;
+    }
+    let end = (start + 2).min(n);
+    let [r, g, b] = [0, 1, 2].map(|i| (start..end).fold(0, |acc, j| acc * 16 + digit(i, j)));
+    Ok(Color::from_rgba8(r, g, b, 255))
+}
+
+fn hex_digit(c: u8) -> u8 {
+    (c as char).to_digit(16).unwrap_or(0) as u8
+}
+
+#[cfg(feature = "named-colors")]
+fn named_color(s: &str) -> Option<[u8; 3]> {
+    let mut buf = [0; 32];
+    let buf = buf.get_mut(..s.len())?;
+    buf.copy_from_slice(s.as_bytes());
+    buf.make_ascii_lowercase();
+    NAMED_COLORS.get(core::str::from_utf8(buf).ok()?).copied()
+}
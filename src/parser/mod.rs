@@ -1,6 +1,27 @@
-use std::{error, fmt};
-
-use crate::Color;
+use core::fmt;
+
+use crate::colorspace::mix;
+use crate::{
+    Color, ColorScheme, ColorSpace, Context, HueInterpolation, Notation, ParsedColor, SystemColor,
+};
+
+mod calc;
+mod html;
+mod parse_error;
+mod tokenizer;
+#[cfg(feature = "alloc")]
+mod vars;
+
+use calc::Value;
+pub use html::parse_html_legacy;
+use parse_error::Error;
+pub use parse_error::Expected;
+#[cfg(feature = "alloc")]
+pub use parse_error::ParseError;
+use tokenizer::{name, Name, Token, Tokenizer};
+#[cfg(feature = "alloc")]
+pub use vars::{parse_with_vars, VarError, VarResolver};
+use ParseColorError::*;
 
 #[cfg(feature = "named-colors")]
 mod named_colors;
@@ -8,6 +29,14 @@ mod named_colors;
 #[cfg(feature = "named-colors")]
 pub use named_colors::NAMED_COLORS;
 
+#[cfg(feature = "x11")]
+mod x11;
+#[cfg(feature = "x11")]
+mod x11_colors;
+
+#[cfg(feature = "x11")]
+pub use x11::X11_COLORS;
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum ParseColorError {
     InvalidHex,
@@ -15,13 +44,16 @@ pub enum ParseColorError {
     InvalidHsl,
     InvalidHwb,
     InvalidHsv,
-    #[cfg(feature = "lab")]
     InvalidLab,
-    #[cfg(feature = "lab")]
     InvalidLch,
     InvalidOklab,
     InvalidOklch,
+    InvalidColor,
+    InvalidColorMix,
+    InvalidLightDark,
     InvalidFunction,
+    /// `currentcolor`, a system color or `light-dark()` without a [`Context`].
+    NeedsContext,
     InvalidUnknown,
 }
 
@@ -33,19 +65,22 @@ impl fmt::Display for ParseColorError {
             Self::InvalidHsl => f.write_str("invalid hsl format"),
             Self::InvalidHwb => f.write_str("invalid hwb format"),
             Self::InvalidHsv => f.write_str("invalid hsv format"),
-            #[cfg(feature = "lab")]
             Self::InvalidLab => f.write_str("invalid lab format"),
-            #[cfg(feature = "lab")]
             Self::InvalidLch => f.write_str("invalid lch format"),
             Self::InvalidOklab => f.write_str("invalid oklab format"),
             Self::InvalidOklch => f.write_str("invalid oklch format"),
+            Self::InvalidColor => f.write_str("invalid color() format"),
+            Self::InvalidColorMix => f.write_str("invalid color-mix() format"),
+            Self::InvalidLightDark => f.write_str("invalid light-dark() format"),
             Self::InvalidFunction => f.write_str("invalid color function"),
+            Self::NeedsContext => f.write_str("color needs a resolution context"),
             Self::InvalidUnknown => f.write_str("invalid unknown format"),
         }
     }
 }
 
-impl error::Error for ParseColorError {}
+#[cfg(feature = "std")]
+impl std::error::Error for ParseColorError {}
 
 /// Parse CSS color string
 ///
@@ -77,341 +112,1273 @@ impl error::Error for ParseColorError {}
 /// # }
 /// ```
 pub fn parse(s: &str) -> Result<Color, ParseColorError> {
-    let s = s.trim().to_lowercase();
+    Parser::new().parse(s)
+}
+
+/// Parse CSS color string, like [`parse`], resolving `currentcolor`, system
+/// colors and `light-dark()` with `context`
+///
+/// # Examples
+///
+/// ```
+/// use csscolorparser::{parse_with_context, ColorScheme, Context};
+///
+/// let ctx = Context::new(ColorScheme::Dark);
+/// let c = parse_with_context("light-dark(white, black)", &ctx).unwrap();
+///
+/// assert_eq!(c.to_rgba8(), [0, 0, 0, 255]);
+/// ```
+pub fn parse_with_context(s: &str, context: &Context) -> Result<Color, ParseColorError> {
+    Parser::new().parse_with_context(s, context)
+}
+
+/// Parse CSS color string, like [`parse`], but with a detailed error
+///
+/// The error has the byte range of the offending token in `s` and what was
+/// expected there.
+///
+/// # Examples
+///
+/// ```
+/// use csscolorparser::{Expected, ParseColorError};
+///
+/// let err = csscolorparser::parse_detailed("rgb(255, 0, 8s)").unwrap_err();
+///
+/// assert_eq!(err.kind(), ParseColorError::InvalidRgb);
+/// assert_eq!(err.span(), 12..14);
+/// assert_eq!(err.token(), "8s");
+/// assert_eq!(err.expected(), Expected::NumberOrPercentage);
+/// ```
+#[cfg(feature = "alloc")]
+pub fn parse_detailed(s: &str) -> Result<Color, ParseError> {
+    Parser::new().parse_detailed(s)
+}
+
+/// CSS color parser with configurable extensions
+///
+/// [`Parser::new()`] accepts the same colors as [`parse()`], including the
+/// extensions that are not in the CSS standard. [`Parser::strict()`] turns
+/// them all off and follows the CSS Color 4 grammar exactly, e.g. legacy
+/// comma separated syntax is only valid in `rgb()`, `rgba()`, `hsl()` and
+/// `hsla()`, and can't have `none` or mix numbers and percentages.
+///
+/// # Examples
+///
+/// ```
+/// use csscolorparser::Parser;
+///
+/// let parser = Parser::strict();
+///
+/// assert!(parser.parse("rgb(255 0 0 / 50%)").is_ok());
+/// assert!(parser.parse("ff0000").is_err());
+/// assert!(parser.parse("rgb(255, 0, none)").is_err());
+///
+/// let parser = Parser::strict().hex_without_hash(true);
+///
+/// assert!(parser.parse("ff0000").is_ok());
+/// ```
+///
+/// Custom named colors:
+///
+/// ```
+/// use csscolorparser::{NameTable, Parser};
+///
+/// const ACME: NameTable = &[("acme-blue", [0, 87, 183]), ("acme-orange", [255, 120, 0])];
+///
+/// let parser = Parser::new().names(&[ACME]);
+/// let c = parser.parse("Acme-Blue").unwrap();
+///
+/// assert_eq!(c.to_rgba8(), [0, 87, 183, 255]);
+/// assert_eq!(c.name_in(&parser), Some("acme-blue"));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Parser<'a> {
+    hex_without_hash: bool,
+    hsv: bool,
+    hwba: bool,
+    mixed_syntax: bool,
+    number_fractions: bool,
+    named_colors: bool,
+    names: &'a [NameTable<'a>],
+    #[cfg(feature = "x11")]
+    x11: bool,
+}
 
-    if s == "transparent" {
-        return Ok(Color::new(0.0, 0.0, 0.0, 0.0));
+/// Table of custom named colors for [`Parser::names()`]
+pub type NameTable<'a> = &'a [(&'a str, [u8; 3])];
+
+impl<'a> Parser<'a> {
+    /// Parser with all extensions enabled, the same as [`parse()`]
+    pub const fn new() -> Self {
+        Self {
+            hex_without_hash: true,
+            hsv: true,
+            hwba: true,
+            mixed_syntax: true,
+            number_fractions: true,
+            named_colors: true,
+            names: &[],
+            #[cfg(feature = "x11")]
+            x11: false,
+        }
     }
 
-    // Named colors
-    #[cfg(feature = "named-colors")]
-    if let Some([r, g, b]) = NAMED_COLORS.get(&*s) {
-        return Ok(Color::from_rgba8(*r, *g, *b, 255));
+    /// Parser with all extensions disabled, following the CSS Color 4 grammar
+    pub const fn strict() -> Self {
+        Self {
+            hex_without_hash: false,
+            hsv: false,
+            hwba: false,
+            mixed_syntax: false,
+            number_fractions: false,
+            named_colors: true,
+            names: &[],
+            #[cfg(feature = "x11")]
+            x11: false,
+        }
     }
 
-    // Hex format
-    if let Some(s) = s.strip_prefix('#') {
-        return parse_hex(s);
+    /// Hex format without the `#` prefix, e.g. `ff0000`
+    pub fn hex_without_hash(mut self, enable: bool) -> Self {
+        self.hex_without_hash = enable;
+        self
     }
 
-    if let (Some(i), Some(s)) = (s.find('('), s.strip_suffix(')')) {
-        let fname = &s[..i].trim_end();
-        let s = &s[i + 1..].replace([',', '/'], " ");
-        let params = s.split_whitespace().collect::<Vec<&str>>();
-        let p_len = params.len();
+    /// `hsv()` and `hsva()`
+    pub fn hsv(mut self, enable: bool) -> Self {
+        self.hsv = enable;
+        self
+    }
 
-        match *fname {
-            "rgb" | "rgba" => {
-                if p_len != 3 && p_len != 4 {
-                    return Err(ParseColorError::InvalidRgb);
-                }
+    /// `hwba()`
+    pub fn hwba(mut self, enable: bool) -> Self {
+        self.hwba = enable;
+        self
+    }
 
-                let r = parse_percent_or_255(params[0]);
-                let g = parse_percent_or_255(params[1]);
-                let b = parse_percent_or_255(params[2]);
+    /// Mixing the legacy and modern syntax of color functions:
+    ///
+    /// * comma separated arguments in any color function, e.g. `hwb(0, 0%, 0%)`
+    /// * `none` and relative color syntax with comma separated arguments
+    /// * numbers for saturation and lightness in legacy `hsl()`, e.g. `hsl(0, 1, 0.5)`
+    /// * alpha without `/`, e.g. `rgb(255 0 0 50%)`
+    /// * whitespace between the function name and `(`, e.g. `rgb (255 0 0)`
+    pub fn mixed_syntax(mut self, enable: bool) -> Self {
+        self.mixed_syntax = enable;
+        self
+    }
 
-                let a = if p_len == 4 {
-                    parse_percent_or_float(params[3])
-                } else {
-                    Some((1.0, true))
-                };
+    /// Numbers for saturation, lightness, whiteness, blackness and value in
+    /// `hsl()`, `hwb()` and `hsv()` are in the range [0..1], e.g.
+    /// `hsl(0 1 0.5)`. Otherwise they are in the range [0..100] like in CSS.
+    pub fn number_fractions(mut self, enable: bool) -> Self {
+        self.number_fractions = enable;
+        self
+    }
 
-                if let (Some((r, r_fmt)), Some((g, g_fmt)), Some((b, b_fmt)), Some((a, _))) =
-                    (r, g, b, a)
-                {
-                    if r_fmt == g_fmt && g_fmt == b_fmt {
-                        return Ok(Color {
-                            r: r.clamp(0.0, 1.0),
-                            g: g.clamp(0.0, 1.0),
-                            b: b.clamp(0.0, 1.0),
-                            a: a.clamp(0.0, 1.0),
-                        });
-                    }
-                }
+    /// The CSS [named colors](https://www.w3.org/TR/css-color-4/#named-colors),
+    /// turn off to only accept the names of [`Parser::names()`]. Requires the
+    /// `named-colors` feature.
+    pub fn named_colors(mut self, enable: bool) -> Self {
+        self.named_colors = enable;
+        self
+    }
 
-                return Err(ParseColorError::InvalidRgb);
-            }
-            "hsl" | "hsla" => {
-                if p_len != 3 && p_len != 4 {
-                    return Err(ParseColorError::InvalidHsl);
-                }
+    /// Custom named colors, looked up ASCII case-insensitively before the
+    /// CSS keywords. The first table with the name wins.
+    pub fn names(mut self, tables: &'a [NameTable<'a>]) -> Self {
+        self.names = tables;
+        self
+    }
 
-                let h = parse_angle(params[0]);
-                let s = parse_percent_or_float(params[1]);
-                let l = parse_percent_or_float(params[2]);
+    /// [X11 color names](https://gitlab.freedesktop.org/xorg/app/rgb/-/blob/master/rgb.txt),
+    /// e.g. `DarkSlateGray4`, `gray73` or `light goldenrod`, and the
+    /// `XParseColor` syntaxes: `rgb:ff/80/00`, `rgbi:1/0.5/0`,
+    /// `CIEXYZ:0.4/0.2/0.1`, `CIExyY`, `CIEuvY`, `CIELab`, `CIELuv` with
+    /// the D65 white point, and `#rrrgggbbb` and `#rrrrggggbbbb`.
+    ///
+    /// The X11 names win where X11 and CSS disagree: `gray`, `green`,
+    /// `maroon` and `purple`. Use `web gray` or `WebGray` for the CSS
+    /// colors. `#rgb` and `#rrggbb` keep their CSS meaning. Requires the
+    /// `x11` feature.
+    #[cfg(feature = "x11")]
+    pub fn x11(mut self, enable: bool) -> Self {
+        self.x11 = enable;
+        self
+    }
 
-                let a = if p_len == 4 {
-                    parse_percent_or_float(params[3])
-                } else {
-                    Some((1.0, true))
-                };
+    /// Parse CSS color string, see [`parse()`]
+    pub fn parse(&self, s: &str) -> Result<Color, ParseColorError> {
+        self.env(None).parse(s).map_err(|e| e.kind)
+    }
 
-                if let (Some(h), Some((s, s_fmt)), Some((l, l_fmt)), Some((a, _))) = (h, s, l, a) {
-                    if s_fmt == l_fmt {
-                        return Ok(Color::from_hsla(h, s, l, a));
-                    }
-                }
+    /// Parse CSS color string keeping the color space and notation it is
+    /// written in, see [`ParsedColor`]
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use csscolorparser::Parser;
+    ///
+    /// let c = Parser::new().parse_preserved("color(display-p3 1 0.5 none)").unwrap();
+    ///
+    /// assert_eq!(c.components(), [Some(1.0), Some(0.5), None]);
+    /// assert_eq!(c.to_string(), "color(display-p3 1 0.5 none)");
+    /// ```
+    pub fn parse_preserved(&self, s: &str) -> Result<ParsedColor, ParseColorError> {
+        parse_components(&self.env(None), s).map_err(|e| e.kind)
+    }
 
-                return Err(ParseColorError::InvalidHsl);
-            }
-            "hwb" | "hwba" => {
-                if p_len != 3 && p_len != 4 {
-                    return Err(ParseColorError::InvalidHwb);
-                }
+    /// Parse CSS color string with a detailed error, see [`parse_detailed()`]
+    #[cfg(feature = "alloc")]
+    pub fn parse_detailed(&self, s: &str) -> Result<Color, ParseError> {
+        self.env(None).parse(s).map_err(|e| e.into_parse_error(s))
+    }
 
-                let h = parse_angle(params[0]);
-                let w = parse_percent_or_float(params[1]);
-                let b = parse_percent_or_float(params[2]);
+    /// Parse CSS color string, resolving the colors that need a context,
+    /// see [`parse_with_context()`]
+    pub fn parse_with_context(&self, s: &str, context: &Context) -> Result<Color, ParseColorError> {
+        self.env(Some(context)).parse(s).map_err(|e| e.kind)
+    }
 
-                let a = if p_len == 4 {
-                    parse_percent_or_float(params[3])
-                } else {
-                    Some((1.0, true))
-                };
+    /// Parse CSS color string with a context and a detailed error
+    #[cfg(feature = "alloc")]
+    pub fn parse_detailed_with_context(
+        &self,
+        s: &str,
+        context: &Context,
+    ) -> Result<Color, ParseError> {
+        self.env(Some(context))
+            .parse(s)
+            .map_err(|e| e.into_parse_error(s))
+    }
 
-                if let (Some(h), Some((w, w_fmt)), Some((b, b_fmt)), Some((a, _))) = (h, w, b, a) {
-                    if w_fmt == b_fmt {
-                        return Ok(Color::from_hwba(h, w, b, a));
-                    }
-                }
+    fn env<'p>(&'p self, context: Option<&'p Context>) -> Env<'p> {
+        Env {
+            parser: self,
+            context,
+            depth: 0,
+        }
+    }
 
-                return Err(ParseColorError::InvalidHwb);
-            }
-            "hsv" | "hsva" => {
-                if p_len != 3 && p_len != 4 {
-                    return Err(ParseColorError::InvalidHsv);
-                }
+    fn custom_color(&self, name: &str) -> Option<[u8; 3]> {
+        self.names
+            .iter()
+            .flat_map(|table| table.iter())
+            .find(|(k, _)| k.eq_ignore_ascii_case(name))
+            .map(|&(_, rgb)| rgb)
+    }
 
-                let h = parse_angle(params[0]);
-                let s = parse_percent_or_float(params[1]);
-                let v = parse_percent_or_float(params[2]);
+    // The name of `rgb` in the custom tables, then in the CSS named colors.
+    pub(crate) fn color_name(&self, rgb: [u8; 3]) -> Option<&'a str> {
+        let mut custom = self.names.iter().flat_map(|table| table.iter());
+        if let Some(&(k, _)) = custom.find(|(_, v)| *v == rgb) {
+            return Some(k);
+        }
+        #[cfg(feature = "named-colors")]
+        if self.named_colors {
+            return NAMED_COLORS
+                .entries()
+                .find(|(_, v)| **v == rgb)
+                .map(|(k, _)| *k);
+        }
+        None
+    }
+}
 
-                let a = if p_len == 4 {
-                    parse_percent_or_float(params[3])
-                } else {
-                    Some((1.0, true))
-                };
+impl Default for Parser<'_> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 
-                if let (Some(h), Some((s, s_fmt)), Some((v, v_fmt)), Some((a, _))) = (h, s, v, a) {
-                    if s_fmt == v_fmt {
-                        return Ok(Color::from_hsva(h, s, v, a));
-                    }
-                }
+// Colors nested in colors, e.g. the origin of a relative color, are limited
+// to this depth.
+const MAX_DEPTH: usize = 32;
+
+// Everything the parse functions need besides the input.
+#[derive(Clone, Copy)]
+struct Env<'p> {
+    parser: &'p Parser<'p>,
+    context: Option<&'p Context>,
+    // How deep the color being parsed is nested in other colors
+    depth: usize,
+}
 
-                return Err(ParseColorError::InvalidHsv);
-            }
-            #[cfg(feature = "lab")]
-            "lab" => {
-                if p_len != 3 && p_len != 4 {
-                    return Err(ParseColorError::InvalidLab);
-                }
+impl Env<'_> {
+    fn parse<'a>(&self, s: &'a str) -> Result<Color, Error<'a>> {
+        Ok(parse_components(self, s)?.to_color())
+    }
 
-                let l = parse_percent_or_float(params[0]);
-                let a = parse_percent_or_float(params[1]);
-                let b = parse_percent_or_float(params[2]);
+    fn context<'a>(&self, token: &'a str) -> Result<&Context, Error<'a>> {
+        self.context
+            .ok_or_else(|| Error::new(NeedsContext, token, Expected::Context))
+    }
 
-                let alpha = if p_len == 4 {
-                    parse_percent_or_float(params[3])
-                } else {
-                    Some((1.0, true))
-                };
+    // Parse `s`, a color nested in a color function with the error `kind`.
+    fn nested<'a>(&self, kind: ParseColorError, s: &'a str) -> Result<ParsedColor, Error<'a>> {
+        if self.depth >= MAX_DEPTH {
+            return Err(Error::new(kind, s, Expected::Nesting(MAX_DEPTH)));
+        }
+        let env = Env {
+            depth: self.depth + 1,
+            ..*self
+        };
+        parse_components(&env, s)
+    }
+}
 
-                if let (Some((l, l_fmt)), Some((a, a_fmt)), Some((b, b_fmt)), Some((alpha, _))) =
-                    (l, a, b, alpha)
-                {
-                    let l = if l_fmt { l * 100.0 } else { l };
-                    let a = if a_fmt {
-                        remap(a, -1.0, 1.0, -125.0, 125.0)
-                    } else {
-                        a
-                    };
-                    let b = if b_fmt {
-                        remap(b, -1.0, 1.0, -125.0, 125.0)
-                    } else {
-                        b
-                    };
-                    return Ok(Color::from_laba(l.max(0.0), a, b, alpha));
+// Parse into the components of the color space the color is written in,
+// keeping missing components (`none`) as NaN.
+fn parse_components<'a>(p: &Env, s: &'a str) -> Result<ParsedColor, Error<'a>> {
+    let mut tokens = Tokenizer::new(s);
+    tokens.skip_whitespace();
+
+    let (start, token) = match tokens.next_spanned() {
+        Some(t) => t,
+        None => return Err(Error::new(InvalidUnknown, end_of(s), Expected::Color)),
+    };
+    let raw = &s[start..tokens.position()];
+
+    match token {
+        Token::Hash(hex) => {
+            if let Some(token) = next_token(s, &tokens) {
+                return Err(Error::new(InvalidHex, token, Expected::EndOfInput));
+            }
+            #[cfg(feature = "x11")]
+            if p.parser.x11 {
+                if let Some(c) = x11::parse_hex(hex) {
+                    return Ok(ParsedColor::from_values(Notation::Hex, c));
                 }
+            }
+            return parse_hex(&name(hex))
+                .map(|c| srgb(Notation::Hex, &c))
+                .map_err(|_| Error::new(InvalidHex, raw, Expected::HexDigits));
+        }
+        Token::Function(fname) => {
+            return parse_function(p, fname, s, tokens);
+        }
+        Token::Ident(id) => {
+            // Whitespace before the parenthesis, e.g. `rgb (0 0 0)`
+            let mut t = tokens.clone();
+            t.skip_whitespace();
+            if p.parser.mixed_syntax && t.next() == Some(Token::OpenParen) {
+                return parse_function(p, id, s, t);
+            }
+
+            let id = name(id);
 
-                return Err(ParseColorError::InvalidLab);
+            if let Some([r, g, b]) = p.parser.custom_color(&id) {
+                expect_end(s, &tokens)?;
+                return Ok(named(&id, &Color::from_rgba8(r, g, b, 255)));
             }
-            #[cfg(feature = "lab")]
-            "lch" => {
-                if p_len != 3 && p_len != 4 {
-                    return Err(ParseColorError::InvalidLch);
-                }
 
-                let l = parse_percent_or_float(params[0]);
-                let c = parse_percent_or_float(params[1]);
-                let h = parse_angle(params[2]);
+            if id == "transparent" {
+                expect_end(s, &tokens)?;
+                return Ok(named(&id, &Color::new(0.0, 0.0, 0.0, 0.0)));
+            }
 
-                let alpha = if p_len == 4 {
-                    parse_percent_or_float(params[3])
-                } else {
-                    Some((1.0, true))
-                };
+            if id == "currentcolor" {
+                expect_end(s, &tokens)?;
+                let ctx = p.context(raw)?;
+                return Ok(named(&id, &ctx.current_color));
+            }
+
+            if let Some(color) = SystemColor::from_name(&id) {
+                expect_end(s, &tokens)?;
+                let ctx = p.context(raw)?;
+                return Ok(named(&id, &ctx.system_colors[color as usize]));
+            }
 
-                if let (Some((l, l_fmt)), Some((c, c_fmt)), Some(h), Some((alpha, _))) =
-                    (l, c, h, alpha)
-                {
-                    let l = if l_fmt { l * 100.0 } else { l };
-                    let c = if c_fmt { c * 150.0 } else { c };
-                    return Ok(Color::from_lcha(
-                        l.max(0.0),
-                        c.max(0.0),
-                        h.to_radians(),
-                        alpha,
-                    ));
+            // X11 names may have spaces, e.g. `navy blue`
+            #[cfg(feature = "x11")]
+            if p.parser.x11 {
+                let single = || x11::named(&id).filter(|_| next_token(s, &tokens).is_none());
+                if let Some([r, g, b]) = x11::named(s.trim()).or_else(single) {
+                    return Ok(named(s.trim(), &Color::from_rgba8(r, g, b, 255)));
                 }
+            }
 
-                return Err(ParseColorError::InvalidLch);
+            // Named colors
+            #[cfg(feature = "named-colors")]
+            if let Some([r, g, b]) = NAMED_COLORS.get(&*id).filter(|_| p.parser.named_colors) {
+                expect_end(s, &tokens)?;
+                return Ok(named(&id, &Color::from_rgba8(*r, *g, *b, 255)));
             }
-            "oklab" => {
-                if p_len != 3 && p_len != 4 {
-                    return Err(ParseColorError::InvalidOklab);
-                }
+        }
+        _ => {}
+    }
 
-                let l = parse_percent_or_float(params[0]);
-                let a = parse_percent_or_float(params[1]);
-                let b = parse_percent_or_float(params[2]);
+    // Hex format without prefix '#'
+    if p.parser.hex_without_hash {
+        if let Ok(c) = parse_hex(s.trim()) {
+            return Ok(srgb(Notation::Hex, &c));
+        }
+    }
 
-                let alpha = if p_len == 4 {
-                    parse_percent_or_float(params[3])
-                } else {
-                    Some((1.0, true))
-                };
+    // `XParseColor` syntaxes, e.g. `rgb:ff/80/00`
+    #[cfg(feature = "x11")]
+    if p.parser.x11 {
+        if let Some(c) = x11::parse(s.trim()) {
+            return Ok(ParsedColor::mixed(c.0, c.1));
+        }
+    }
 
-                if let (Some((l, _)), Some((a, a_fmt)), Some((b, b_fmt)), Some((alpha, _))) =
-                    (l, a, b, alpha)
-                {
-                    let a = if a_fmt {
-                        remap(a, -1.0, 1.0, -0.4, 0.4)
-                    } else {
-                        a
-                    };
-                    let b = if b_fmt {
-                        remap(b, -1.0, 1.0, -0.4, 0.4)
-                    } else {
-                        b
-                    };
-                    return Ok(Color::from_oklaba(l.max(0.0), a, b, alpha));
-                }
+    Err(Error::new(InvalidUnknown, raw, Expected::Color))
+}
 
-                return Err(ParseColorError::InvalidOklab);
-            }
-            "oklch" => {
-                if p_len != 3 && p_len != 4 {
-                    return Err(ParseColorError::InvalidOklch);
+fn srgb(notation: Notation, c: &Color) -> ParsedColor {
+    ParsedColor::from_values(notation, (ColorSpace::Srgb, c.to_array()))
+}
+
+fn named(name: &str, c: &Color) -> ParsedColor {
+    srgb(Notation::Named, c).with_name(name)
+}
+
+// Empty slice at the end of `s`.
+fn end_of(s: &str) -> &str {
+    &s[s.len()..]
+}
+
+// The next token of `tokens`, which is over `s`, ignoring whitespace.
+fn next_token<'a>(s: &'a str, tokens: &Tokenizer<'a>) -> Option<&'a str> {
+    let mut t = tokens.clone();
+    t.skip_whitespace();
+    let (start, _) = t.next_spanned()?;
+    Some(&s[start..t.position()])
+}
+
+fn expect_end<'a>(s: &'a str, tokens: &Tokenizer<'a>) -> Result<(), Error<'a>> {
+    match next_token(s, tokens) {
+        Some(token) => Err(Error::new(InvalidUnknown, token, Expected::EndOfInput)),
+        None => Ok(()),
+    }
+}
+
+// Parse the arguments of the color function with the raw name `fname`,
+// `tokens` is over `s` and starts right after the opening parenthesis.
+fn parse_function<'a>(
+    p: &Env,
+    fname: &'a str,
+    s: &'a str,
+    tokens: Tokenizer<'a>,
+) -> Result<ParsedColor, Error<'a>> {
+    // Find the closing parenthesis, nothing but whitespace may follow it
+    let mut tokens = tokens.structure_only();
+    let start = tokens.position();
+    let mut depth = 0;
+    let end = loop {
+        let pos = tokens.position();
+        match tokens.next() {
+            Some(Token::Function(_)) | Some(Token::OpenParen) => depth += 1,
+            Some(Token::CloseParen) if depth == 0 => break pos,
+            Some(Token::CloseParen) => depth -= 1,
+            Some(_) => {}
+            None => return Err(Error::new(InvalidUnknown, end_of(s), Expected::CloseParen)),
+        }
+    };
+    expect_end(s, &tokens)?;
+    let body = &s[start..end];
+
+    let kind = match &*name(fname) {
+        "rgb" | "rgba" => InvalidRgb,
+        "hsl" | "hsla" => InvalidHsl,
+        "hwb" => InvalidHwb,
+        "hwba" if p.parser.hwba => InvalidHwb,
+        "hsv" | "hsva" if p.parser.hsv => InvalidHsv,
+        "lab" => InvalidLab,
+        "lch" => InvalidLch,
+        "oklab" => InvalidOklab,
+        "oklch" => InvalidOklch,
+        "color" => InvalidColor,
+        "color-mix" => {
+            return parse_color_mix(p, body).map(|(space, c)| ParsedColor::mixed(space, c))
+        }
+        "light-dark" => return parse_light_dark(p, fname, body),
+        _ => return Err(Error::new(InvalidFunction, fname, Expected::ColorFunction)),
+    };
+    let notation = match kind {
+        InvalidColor => Notation::ColorFunction,
+        _ => Notation::Function,
+    };
+    parse_arguments(p, kind, body).map(|c| ParsedColor::from_values(notation, c))
+}
+
+// The arguments of the color function `kind`, with or without an origin color.
+fn parse_arguments<'a>(
+    p: &Env,
+    kind: ParseColorError,
+    body: &'a str,
+) -> Result<Components, Error<'a>> {
+    let Params {
+        args: params,
+        legacy,
+        slash,
+    } = split_params(body).map_err(|(token, expected)| Error::new(kind, token, expected))?;
+    let found = params.len();
+    let params = params.as_slice();
+
+    // Only rgb() and hsl() have a legacy syntax in CSS, hsv() is not CSS and
+    // takes both
+    if legacy && !p.parser.mixed_syntax && !matches!(kind, InvalidRgb | InvalidHsl | InvalidHsv) {
+        return Err(Error::new(kind, params[1], Expected::Whitespace));
+    }
+
+    // Relative color syntax: `rgb(from <color> r g b)`
+    let (origin, params, found) = match params.split_first() {
+        Some((first, rest))
+            if is_keyword(first, "from")
+                && !rest.is_empty()
+                && (!legacy || p.parser.mixed_syntax) =>
+        {
+            let origin = p.nested(kind, rest[0])?.to_color();
+            (Some(origin), &rest[1..], found - 2)
+        }
+        _ => (None, params, found),
+    };
+
+    let mut args = Args {
+        parser: p.parser,
+        kind,
+        body,
+        params,
+        found,
+        legacy,
+        slash,
+        ch: None,
+    };
+
+    match kind {
+        InvalidRgb => {
+            args.count(3, "3 or 4")?;
+            args.ch = origin.map(|c| {
+                let [r, g, b, a] = c.to_array();
+                Channels::new(["r", "g", "b"], [r * 255.0, g * 255.0, b * 255.0, a])
+            });
+
+            let r = args.percent_or_255(0)?;
+            let g = args.percent_or_255(1)?;
+            let b = args.percent_or_255(2)?;
+            let a = args.alpha(3)?;
+            args.same_format(0, &[r, g, b])?;
+
+            Ok((ColorSpace::Srgb, [r.0, g.0, b.0, a]))
+        }
+        InvalidHsl => {
+            args.count(3, "3 or 4")?;
+            args.ch = origin.map(|c| {
+                let [h, s, l, a] = c.to_hsla();
+                Channels::new(["h", "s", "l"], [h, s * 100.0, l * 100.0, a])
+            });
+
+            let h = args.angle(0)?;
+            let s = args.fraction(1)?;
+            let l = args.fraction(2)?;
+            let a = args.alpha(3)?;
+            args.same_format(1, &[s, l])?;
+            let (s, l) = args.saturation(s.0, l.0);
+
+            Ok((ColorSpace::Hsl, [h, s, l, a]))
+        }
+        InvalidHwb => {
+            args.count(3, "3 or 4")?;
+            args.ch = origin.map(|c| {
+                let [h, w, b, a] = c.to_hwba();
+                Channels::new(["h", "w", "b"], [h, w * 100.0, b * 100.0, a])
+            });
+
+            let h = args.angle(0)?;
+            let w = args.fraction(1)?;
+            let b = args.fraction(2)?;
+            let a = args.alpha(3)?;
+            args.same_format(1, &[w, b])?;
+
+            Ok((ColorSpace::Hwb, [h, w.0, b.0, a]))
+        }
+        InvalidHsv => {
+            args.count(3, "3 or 4")?;
+            args.ch = origin.map(|c| {
+                let [h, s, v, a] = c.to_hsva();
+                Channels::new(["h", "s", "v"], [h, s * 100.0, v * 100.0, a])
+            });
+
+            let h = args.angle(0)?;
+            let s = args.fraction(1)?;
+            let v = args.fraction(2)?;
+            let a = args.alpha(3)?;
+            args.same_format(1, &[s, v])?;
+            let (s, v) = args.saturation(s.0, v.0);
+
+            Ok((ColorSpace::Hsv, [h, s, v, a]))
+        }
+        InvalidLab => {
+            args.count(3, "3 or 4")?;
+            args.ch = origin.map(|c| Channels::new(["l", "a", "b"], c.to_laba()));
+
+            let (l, l_fmt) = args.percent_or_float(0)?;
+            let (a, a_fmt) = args.percent_or_float(1)?;
+            let (b, b_fmt) = args.percent_or_float(2)?;
+            let alpha = args.alpha(3)?;
+
+            let l = if l_fmt { l * 100.0 } else { l };
+            let a = if a_fmt {
+                remap(a, -1.0, 1.0, -125.0, 125.0)
+            } else {
+                a
+            };
+            let b = if b_fmt {
+                remap(b, -1.0, 1.0, -125.0, 125.0)
+            } else {
+                b
+            };
+            Ok((ColorSpace::Lab, [non_negative(l), a, b, alpha]))
+        }
+        InvalidLch => {
+            args.count(3, "3 or 4")?;
+            args.ch = origin.map(|c| {
+                let [l, c, h, alpha] = c.to_lcha();
+                Channels::new(["l", "c", "h"], [l, c, h.to_degrees(), alpha])
+            });
+
+            let (l, l_fmt) = args.percent_or_float(0)?;
+            let (c, c_fmt) = args.percent_or_float(1)?;
+            let h = args.angle(2)?;
+            let alpha = args.alpha(3)?;
+
+            let l = if l_fmt { l * 100.0 } else { l };
+            let c = if c_fmt { c * 150.0 } else { c };
+            Ok((
+                ColorSpace::Lch,
+                [non_negative(l), non_negative(c), h, alpha],
+            ))
+        }
+        InvalidOklab => {
+            args.count(3, "3 or 4")?;
+            args.ch = origin.map(|c| Channels::new(["l", "a", "b"], c.to_oklaba()));
+
+            let (l, _) = args.percent_or_float(0)?;
+            let (a, a_fmt) = args.percent_or_float(1)?;
+            let (b, b_fmt) = args.percent_or_float(2)?;
+            let alpha = args.alpha(3)?;
+
+            let a = if a_fmt {
+                remap(a, -1.0, 1.0, -0.4, 0.4)
+            } else {
+                a
+            };
+            let b = if b_fmt {
+                remap(b, -1.0, 1.0, -0.4, 0.4)
+            } else {
+                b
+            };
+            Ok((ColorSpace::Oklab, [non_negative(l), a, b, alpha]))
+        }
+        InvalidOklch => {
+            args.count(3, "3 or 4")?;
+            args.ch = origin.map(|c| {
+                let [l, c, h, alpha] = c.to_oklcha();
+                Channels::new(["l", "c", "h"], [l, c, h.to_degrees(), alpha])
+            });
+
+            let (l, _) = args.percent_or_float(0)?;
+            let (c, c_fmt) = args.percent_or_float(1)?;
+            let h = args.angle(2)?;
+            let alpha = args.alpha(3)?;
+
+            let c = if c_fmt { c * 0.4 } else { c };
+            Ok((
+                ColorSpace::Oklch,
+                [non_negative(l), non_negative(c), h, alpha],
+            ))
+        }
+        _ => {
+            // color(<space> c1 c2 c3 [/ alpha])
+            args.count(4, "4 or 5")?;
+
+            let space = match ident(params[0]).as_deref() {
+                Some("srgb") => ColorSpace::Srgb,
+                Some("srgb-linear") => ColorSpace::SrgbLinear,
+                Some("display-p3") => ColorSpace::DisplayP3,
+                Some("a98-rgb") => ColorSpace::A98Rgb,
+                Some("prophoto-rgb") => ColorSpace::ProphotoRgb,
+                Some("rec2020") => ColorSpace::Rec2020,
+                Some("xyz") | Some("xyz-d65") => ColorSpace::XyzD65,
+                Some("xyz-d50") => ColorSpace::XyzD50,
+                _ => return Err(args.error(0, Expected::ColorSpace)),
+            };
+
+            args.ch = origin.map(|c| match space {
+                ColorSpace::XyzD65 | ColorSpace::XyzD50 => {
+                    Channels::new(["x", "y", "z"], space.components(&c))
                 }
+                _ => Channels::new(["r", "g", "b"], space.components(&c)),
+            });
 
-                let l = parse_percent_or_float(params[0]);
-                let c = parse_percent_or_float(params[1]);
-                let h = parse_angle(params[2]);
+            let (c1, _) = args.percent_or_float(1)?;
+            let (c2, _) = args.percent_or_float(2)?;
+            let (c3, _) = args.percent_or_float(3)?;
+            let alpha = args.alpha(4)?;
 
-                let alpha = if p_len == 4 {
-                    parse_percent_or_float(params[3])
+            Ok((space, [c1, c2, c3, alpha]))
+        }
+    }
+}
+
+// The arguments of a color function, with the channel keywords of relative
+// color syntax.
+struct Args<'a, 'b> {
+    parser: &'b Parser<'b>,
+    kind: ParseColorError,
+    body: &'a str,
+    params: &'b [&'a str],
+    // Number of arguments, `params` holds at most `MAX_ARGS`
+    found: usize,
+    // Comma separated arguments
+    legacy: bool,
+    // '/' before the last argument
+    slash: bool,
+    ch: Option<Channels>,
+}
+
+impl<'a> Args<'a, '_> {
+    fn error(&self, i: usize, expected: Expected) -> Error<'a> {
+        Error::new(self.kind, self.params[i], expected)
+    }
+
+    // `n` channels and an optional alpha
+    fn count(&self, n: usize, count: &'static str) -> Result<(), Error<'a>> {
+        let found = self.found;
+        if found != n && found != n + 1 {
+            let expected = Expected::Arguments { count, found };
+            return Err(Error::new(self.kind, self.body, expected));
+        }
+        Ok(())
+    }
+
+    fn value<T>(
+        &self,
+        i: usize,
+        parse: fn(&str, &Option<Channels>) -> Option<T>,
+        expected: Expected,
+    ) -> Result<T, Error<'a>> {
+        // The legacy syntax doesn't have missing components
+        if self.legacy && !self.parser.mixed_syntax && is_keyword(self.params[i], "none") {
+            return Err(self.error(i, expected));
+        }
+        parse(self.params[i], &self.ch).ok_or_else(|| self.error(i, expected))
+    }
+
+    fn percent_or_float(&self, i: usize) -> Result<(f32, bool), Error<'a>> {
+        self.value(i, parse_percent_or_float, Expected::NumberOrPercentage)
+    }
+
+    fn percent_or_255(&self, i: usize) -> Result<(f32, bool), Error<'a>> {
+        self.value(i, parse_percent_or_255, Expected::NumberOrPercentage)
+    }
+
+    fn angle(&self, i: usize) -> Result<f32, Error<'a>> {
+        self.value(i, parse_angle, Expected::NumberOrAngle)
+    }
+
+    // The range of plain numbers for saturation, lightness, whiteness,
+    // blackness and value. Relative colors always use the range of CSS, the
+    // channels of the origin are numbers too.
+    fn fraction_scale(&self) -> f32 {
+        if self.parser.number_fractions && self.ch.is_none() {
+            1.0
+        } else {
+            100.0
+        }
+    }
+
+    // Saturation, lightness, whiteness, blackness or value in the range [0..1].
+    // They are percentages in the legacy syntax of CSS.
+    fn fraction(&self, i: usize) -> Result<(f32, bool), Error<'a>> {
+        let (t, percent) = self.percent_or_float(i)?;
+        if percent {
+            return Ok((t, percent));
+        }
+        if self.legacy && !self.parser.mixed_syntax {
+            return Err(self.error(i, Expected::Percentage));
+        }
+        Ok((t / self.fraction_scale(), percent))
+    }
+
+    // CSS clamps a negative saturation to 0, the other channels of hsl() and
+    // hsv() may give colors outside of sRGB. Browsers clamp both channels of
+    // the legacy syntax to [0..1].
+    fn saturation(&self, s: f32, t: f32) -> (f32, f32) {
+        if self.legacy {
+            (s.clamp(0.0, 1.0), t.clamp(0.0, 1.0))
+        } else {
+            (non_negative(s), t)
+        }
+    }
+
+    // Alpha defaults to the origin color's alpha in relative color syntax.
+    fn alpha(&self, i: usize) -> Result<f32, Error<'a>> {
+        if i < self.params.len() {
+            if !self.legacy && !self.slash && !self.parser.mixed_syntax {
+                return Err(self.error(i, Expected::Slash));
+            }
+            return self.percent_or_float(i).map(|(t, _)| t);
+        }
+        Ok(self.ch.as_ref().map_or(1.0, |c| c.values[3]))
+    }
+
+    // In the legacy syntax the values, starting at argument `first`, are all
+    // numbers or all percentages, ignoring `none`. Relative color syntax
+    // allows mixing.
+    fn same_format(&self, first: usize, values: &[(f32, bool)]) -> Result<(), Error<'a>> {
+        if !self.legacy || self.ch.is_some() {
+            return Ok(());
+        }
+        let mut formats = values
+            .iter()
+            .enumerate()
+            .filter(|(_, (t, _))| !t.is_nan())
+            .map(|(i, &(_, f))| (first + i, f));
+        if let Some((_, format)) = formats.next() {
+            if let Some((i, _)) = formats.find(|&(_, f)| f != format) {
+                let expected = if format {
+                    Expected::Percentage
                 } else {
-                    Some((1.0, true))
+                    Expected::Number
                 };
+                return Err(self.error(i, expected));
+            }
+        }
+        Ok(())
+    }
+}
 
-                if let (Some((l, _)), Some((c, c_fmt)), Some(h), Some((alpha, _))) =
-                    (l, c, h, alpha)
-                {
-                    let c = if c_fmt { c * 0.4 } else { c };
-                    return Ok(Color::from_oklcha(
-                        l.max(0.0),
-                        c.max(0.0),
-                        h.to_radians(),
-                        alpha,
-                    ));
-                }
+// color-mix(in <color-space> [<hue-method> hue]?, <color> <percentage>?, <color> <percentage>?)
+fn parse_color_mix<'a>(p: &Env, s: &'a str) -> Result<Components, Error<'a>> {
+    let err = |token, expected| Error::new(InvalidColorMix, token, expected);
+
+    // Comma separated groups of whitespace separated arguments
+    let mut args = [ArgVec::new(); 3];
+    let mut found = 1;
+    for arg in split_args(s) {
+        let (sep, arg) = arg.map_err(|(token, expected)| err(token, expected))?;
+        match sep {
+            Separator::Space => {}
+            Separator::Comma => found += 1,
+            Separator::Slash => return Err(err(arg, Expected::Comma)),
+        }
+        if let Some(group) = args.get_mut(found - 1) {
+            group.push(arg);
+        }
+    }
 
-                return Err(ParseColorError::InvalidOklch);
-            }
-            _ => {
-                return Err(ParseColorError::InvalidFunction);
+    if found != 3 {
+        let expected = Expected::Arguments {
+            count: "3 comma separated",
+            found,
+        };
+        return Err(err(s, expected));
+    }
+
+    let (space, hue) = match *args[0].as_slice() {
+        [kw, space] if is_keyword(kw, "in") => (space, None),
+        [kw, space, method, kw2] if is_keyword(kw, "in") && is_keyword(kw2, "hue") => {
+            (space, Some(method))
+        }
+        [kw, ..] if !is_keyword(kw, "in") => return Err(err(kw, Expected::Keyword("in"))),
+        [_, _, _, kw2] => return Err(err(kw2, Expected::Keyword("hue"))),
+        _ => return Err(err(s, Expected::ColorSpace)),
+    };
+
+    let space_token = space;
+    let space = match ident(space).as_deref().and_then(parse_color_space) {
+        Some(space) => space,
+        None => return Err(err(space_token, Expected::ColorSpace)),
+    };
+
+    let hue = match hue {
+        None => HueInterpolation::Shorter,
+        // Only polar color spaces have a hue
+        Some(method) if space.hue_index().is_none() => return Err(err(method, Expected::Comma)),
+        Some(method) => match ident(method).as_deref() {
+            Some("shorter") => HueInterpolation::Shorter,
+            Some("longer") => HueInterpolation::Longer,
+            Some("increasing") => HueInterpolation::Increasing,
+            Some("decreasing") => HueInterpolation::Decreasing,
+            _ => return Err(err(method, Expected::HueInterpolation)),
+        },
+    };
+
+    let (c1, p1) = parse_mix_color(p, args[1].as_slice())?;
+    let (c2, p2) = parse_mix_color(p, args[2].as_slice())?;
+
+    mix(space, hue, c1, c2, p1, p2)
+        .map(|c| (space, c))
+        .ok_or_else(|| err(s, Expected::NonZeroPercentages))
+}
+
+// light-dark(<color>, <color>)
+fn parse_light_dark<'a>(p: &Env, fname: &'a str, s: &'a str) -> Result<ParsedColor, Error<'a>> {
+    let err = |token, expected| Error::new(InvalidLightDark, token, expected);
+
+    let mut args = ArgVec::new();
+    for arg in split_args(s) {
+        let (sep, arg) = arg.map_err(|(token, expected)| err(token, expected))?;
+        if sep != Separator::Comma && args.len() != 0 {
+            return Err(err(arg, Expected::Comma));
+        }
+        args.push(arg);
+    }
+
+    match *args.as_slice() {
+        [light, dark] => {
+            let light = p.nested(InvalidLightDark, light)?;
+            let dark = p.nested(InvalidLightDark, dark)?;
+            match p.context(fname)?.color_scheme {
+                ColorScheme::Light => Ok(light),
+                ColorScheme::Dark => Ok(dark),
             }
         }
+        _ => {
+            let expected = Expected::Arguments {
+                count: "2 comma separated",
+                found: args.len(),
+            };
+            Err(err(s, expected))
+        }
     }
+}
 
-    // Hex format without prefix '#'
-    if let Ok(c) = parse_hex(&s) {
-        return Ok(c);
+// <color> <percentage>? in any order
+type Components = (ColorSpace, [f32; 4]);
+
+fn parse_mix_color<'a>(p: &Env, args: &[&'a str]) -> Result<(Components, Option<f32>), Error<'a>> {
+    let percent = |s: &'a str| match parse_value(s, &None) {
+        Some(Value::Percentage(t)) if (0.0..=100.0).contains(&t) => Ok(Some(t / 100.0)),
+        Some(Value::Percentage(_)) => Err(Error::new(InvalidColorMix, s, Expected::MixPercentage)),
+        _ => Ok(None),
+    };
+
+    match *args {
+        [c] => Ok((p.nested(InvalidColorMix, c)?.resolved(), None)),
+        [a, b] => match (percent(a)?, percent(b)?) {
+            (Some(t), None) => Ok((p.nested(InvalidColorMix, b)?.resolved(), Some(t))),
+            (None, Some(t)) => Ok((p.nested(InvalidColorMix, a)?.resolved(), Some(t))),
+            (Some(_), Some(_)) => Err(Error::new(InvalidColorMix, b, Expected::Color)),
+            (None, None) => Err(Error::new(InvalidColorMix, b, Expected::Percentage)),
+        },
+        [_, _, c, ..] => Err(Error::new(InvalidColorMix, c, Expected::Comma)),
+        [] => unreachable!(),
     }
+}
 
-    Err(ParseColorError::InvalidUnknown)
+fn parse_color_space(s: &str) -> Option<ColorSpace> {
+    match s {
+        "srgb" => Some(ColorSpace::Srgb),
+        "srgb-linear" => Some(ColorSpace::SrgbLinear),
+        "display-p3" => Some(ColorSpace::DisplayP3),
+        "a98-rgb" => Some(ColorSpace::A98Rgb),
+        "prophoto-rgb" => Some(ColorSpace::ProphotoRgb),
+        "rec2020" => Some(ColorSpace::Rec2020),
+        "lab" => Some(ColorSpace::Lab),
+        "lch" => Some(ColorSpace::Lch),
+        "oklab" => Some(ColorSpace::Oklab),
+        "oklch" => Some(ColorSpace::Oklch),
+        "xyz" | "xyz-d65" => Some(ColorSpace::XyzD65),
+        "xyz-d50" => Some(ColorSpace::XyzD50),
+        "hsl" => Some(ColorSpace::Hsl),
+        "hwb" => Some(ColorSpace::Hwb),
+        _ => None,
+    }
 }
 
 fn parse_hex(s: &str) -> Result<Color, ParseColorError> {
-    if !s.is_ascii() {
+    let n = s.len();
+    if !matches!(n, 3 | 4 | 6 | 8) {
         return Err(ParseColorError::InvalidHex);
     }
 
-    let n = s.len();
+    let mut digits = [0; 8];
+    for (d, c) in digits.iter_mut().zip(s.chars()) {
+        *d = c.to_digit(16).ok_or(ParseColorError::InvalidHex)? as u8;
+    }
 
-    if n == 3 || n == 4 {
-        let r =
-            u8::from_str_radix(&s[0..1].repeat(2), 16).map_err(|_| ParseColorError::InvalidHex)?;
-        let g =
-            u8::from_str_radix(&s[1..2].repeat(2), 16).map_err(|_| ParseColorError::InvalidHex)?;
-        let b =
-            u8::from_str_radix(&s[2..3].repeat(2), 16).map_err(|_| ParseColorError::InvalidHex)?;
+    let [r, g, b, a] = if n <= 4 {
+        let a = if n == 4 { digits[3] * 17 } else { 255 };
+        [digits[0] * 17, digits[1] * 17, digits[2] * 17, a]
+    } else {
+        let byte = |i: usize| digits[i] * 16 + digits[i + 1];
+        let a = if n == 8 { byte(6) } else { 255 };
+        [byte(0), byte(2), byte(4), a]
+    };
 
-        let a = if n == 4 {
-            u8::from_str_radix(&s[3..4].repeat(2), 16).map_err(|_| ParseColorError::InvalidHex)?
-        } else {
-            255
-        };
+    Ok(Color::from_rgba8(r, g, b, a))
+}
 
-        Ok(Color::from_rgba8(r, g, b, a))
-    } else if n == 6 || n == 8 {
-        let r = u8::from_str_radix(&s[0..2], 16).map_err(|_| ParseColorError::InvalidHex)?;
-        let g = u8::from_str_radix(&s[2..4], 16).map_err(|_| ParseColorError::InvalidHex)?;
-        let b = u8::from_str_radix(&s[4..6], 16).map_err(|_| ParseColorError::InvalidHex)?;
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Separator {
+    Space,
+    Comma,
+    Slash,
+}
 
-        let a = if n == 8 {
-            u8::from_str_radix(&s[6..8], 16).map_err(|_| ParseColorError::InvalidHex)?
-        } else {
-            255
-        };
+// Split function arguments into component values, keeping nested functions
+// such as `calc()` or the origin color of relative color syntax together.
+// Each argument comes with the separator before it, the first one with
+// `Separator::Space`. The error is the offending token and what was expected.
+fn split_args(s: &str) -> SplitArgs<'_> {
+    SplitArgs {
+        s,
+        tokens: Tokenizer::new(s).structure_only(),
+        first: true,
+        done: false,
+    }
+}
+
+struct SplitArgs<'a> {
+    s: &'a str,
+    tokens: Tokenizer<'a>,
+    first: bool,
+    done: bool,
+}
+
+impl<'a> Iterator for SplitArgs<'a> {
+    type Item = Result<(Separator, &'a str), (&'a str, Expected)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let arg = self.split();
+        self.done = !matches!(arg, Some(Ok(_)));
+        arg
+    }
+}
+
+impl<'a> SplitArgs<'a> {
+    fn split(&mut self) -> Option<<Self as Iterator>::Item> {
+        let s = self.s;
+        let mut sep = None;
+        let mut start = 0;
+        let mut depth = 0;
+
+        while let Some((pos, token)) = self.tokens.next_spanned() {
+            if depth == 0 {
+                let next_sep = match token {
+                    Token::Whitespace => continue,
+                    Token::Comma => Some(Separator::Comma),
+                    Token::Delim('/') => Some(Separator::Slash),
+                    _ => None,
+                };
+                if let Some(next_sep) = next_sep {
+                    let token = &s[pos..self.tokens.position()];
+                    // Leading or repeated separator
+                    if self.first || sep.is_some() {
+                        return Some(Err((token, Expected::Argument)));
+                    }
+                    sep = Some((next_sep, token));
+                    continue;
+                }
+                start = pos;
+            }
+            match token {
+                Token::Function(_) | Token::OpenParen => depth += 1,
+                Token::CloseParen if depth == 0 => {
+                    return Some(Err((&s[pos..self.tokens.position()], Expected::Argument)));
+                }
+                Token::CloseParen => depth -= 1,
+                _ => {}
+            }
+            if depth == 0 {
+                self.first = false;
+                let sep = sep.map_or(Separator::Space, |(sep, _)| sep);
+                return Some(Ok((sep, &s[start..self.tokens.position()])));
+            }
+        }
+
+        if depth != 0 {
+            return Some(Err((end_of(s), Expected::CloseParen)));
+        }
+        if let Some((_, token)) = sep {
+            return Some(Err((token, Expected::Argument)));
+        }
+        None
+    }
+}
+
+// No color function has more arguments than this, e.g.
+// `color(from <color> <space> c1 c2 c3 / alpha)`
+const MAX_ARGS: usize = 8;
+
+// Arguments on the stack, counting but dropping the ones that don't fit.
+#[derive(Clone, Copy)]
+struct ArgVec<'a> {
+    args: [&'a str; MAX_ARGS],
+    len: usize,
+}
+
+impl<'a> ArgVec<'a> {
+    fn new() -> Self {
+        Self {
+            args: [""; MAX_ARGS],
+            len: 0,
+        }
+    }
+
+    fn push(&mut self, arg: &'a str) {
+        if let Some(slot) = self.args.get_mut(self.len) {
+            *slot = arg;
+        }
+        self.len += 1;
+    }
+
+    fn len(&self) -> usize {
+        self.len
+    }
+
+    fn as_slice(&self) -> &[&'a str] {
+        &self.args[..self.len.min(MAX_ARGS)]
+    }
+}
+
+struct Params<'a> {
+    args: ArgVec<'a>,
+    // Comma separated arguments
+    legacy: bool,
+    // '/' before the last argument
+    slash: bool,
+}
+
+// Function arguments are either all separated by commas (legacy syntax) or by
+// whitespace with an optional '/' before the last one.
+fn split_params(s: &str) -> Result<Params<'_>, (&str, Expected)> {
+    let mut args = ArgVec::new();
+    let mut legacy = false;
+    // The argument after '/', which must be the last one
+    let mut slash = None;
+
+    for (i, arg) in split_args(s).enumerate() {
+        let (sep, arg) = arg?;
+        if i == 1 {
+            legacy = sep == Separator::Comma;
+        }
+        match sep {
+            Separator::Comma if !legacy => return Err((arg, Expected::Whitespace)),
+            Separator::Space | Separator::Slash if legacy => return Err((arg, Expected::Comma)),
+            _ => {}
+        }
+        if let Some(arg) = slash {
+            return Err((arg, Expected::Whitespace));
+        }
+        if sep == Separator::Slash {
+            slash = Some(arg);
+        }
+        args.push(arg);
+    }
+
+    Ok(Params {
+        args,
+        legacy,
+        slash: slash.is_some(),
+    })
+}
 
-        Ok(Color::from_rgba8(r, g, b, a))
+// The name of an argument that is a single identifier, in lowercase.
+fn ident(s: &str) -> Option<Name<'_>> {
+    let mut tokens = Tokenizer::new(s);
+    match (tokens.next(), tokens.next()) {
+        (Some(Token::Ident(raw)), None) => Some(name(raw)),
+        _ => None,
+    }
+}
+
+fn is_keyword(s: &str, keyword: &str) -> bool {
+    ident(s).is_some_and(|s| &*s == keyword)
+}
+
+// Channel keywords of the relative color syntax, resolved to the origin
+// color's components in the color space of the function. The values are on
+// the same scale the function uses for plain numbers, e.g. `r` in `rgb()` is
+// in the range [0..255].
+struct Channels {
+    names: [&'static str; 3],
+    values: [f32; 4],
+}
+
+impl Channels {
+    fn new(names: [&'static str; 3], values: [f32; 4]) -> Self {
+        Self { names, values }
+    }
+
+    fn get(&self, s: &str) -> Option<f32> {
+        if s == "alpha" {
+            return Some(self.values[3]);
+        }
+        self.names
+            .iter()
+            .position(|&name| name == s)
+            .map(|i| self.values[i])
+    }
+}
+
+// Like `t.max(0.0)`, but keeps missing components.
+fn non_negative(t: f32) -> f32 {
+    if t < 0.0 {
+        0.0
     } else {
-        Err(ParseColorError::InvalidHex)
+        t
+    }
+}
+
+// A single argument: `none`, a channel keyword, a math function such as
+// `calc()` or a plain number, percentage or angle.
+fn parse_value(s: &str, ch: &Option<Channels>) -> Option<Value> {
+    let mut tokens = Tokenizer::new(s);
+    let token = tokens.next()?;
+
+    if let Token::Function(raw) = token {
+        if !calc::is_math_function(&name(raw)) {
+            return None;
+        }
+        return calc::eval(s, &|name| ch.as_ref().and_then(|c| c.get(name)));
+    }
+
+    if tokens.next().is_some() {
+        return None;
+    }
+
+    match token {
+        Token::Ident(raw) => {
+            let s = name(raw);
+            if s == "none" {
+                return Some(Value::Number(f32::NAN));
+            }
+            ch.as_ref()?.get(&s).map(Value::Number)
+        }
+        token => calc::dimension(token),
     }
 }
 
-fn parse_percent_or_float(s: &str) -> Option<(f32, bool)> {
-    s.strip_suffix('%')
-        .and_then(|s| s.parse().ok().map(|t: f32| (t / 100.0, true)))
-        .or_else(|| s.parse().ok().map(|t| (t, false)))
+fn parse_percent_or_float(s: &str, ch: &Option<Channels>) -> Option<(f32, bool)> {
+    match parse_value(s, ch)? {
+        Value::Number(t) => Some((t, false)),
+        Value::Percentage(t) => Some((t / 100.0, true)),
+        Value::Angle(_) => None,
+    }
 }
 
-fn parse_percent_or_255(s: &str) -> Option<(f32, bool)> {
-    s.strip_suffix('%')
-        .and_then(|s| s.parse().ok().map(|t: f32| (t / 100.0, true)))
-        .or_else(|| s.parse().ok().map(|t: f32| (t / 255.0, false)))
+fn parse_percent_or_255(s: &str, ch: &Option<Channels>) -> Option<(f32, bool)> {
+    match parse_value(s, ch)? {
+        Value::Number(t) => Some((t / 255.0, false)),
+        Value::Percentage(t) => Some((t / 100.0, true)),
+        Value::Angle(_) => None,
+    }
 }
 
-fn parse_angle(s: &str) -> Option<f32> {
-    s.strip_suffix("deg")
-        .and_then(|s| s.parse().ok())
-        .or_else(|| {
-            s.strip_suffix("grad")
-                .and_then(|s| s.parse().ok())
-                .map(|t: f32| t * 360.0 / 400.0)
-        })
-        .or_else(|| {
-            s.strip_suffix("rad")
-                .and_then(|s| s.parse().ok())
-                .map(|t: f32| t.to_degrees())
-        })
-        .or_else(|| {
-            s.strip_suffix("turn")
-                .and_then(|s| s.parse().ok())
-                .map(|t: f32| t * 360.0)
-        })
-        .or_else(|| s.parse().ok())
+fn parse_angle(s: &str, ch: &Option<Channels>) -> Option<f32> {
+    match parse_value(s, ch)? {
+        Value::Number(t) | Value::Angle(t) => Some(t),
+        Value::Percentage(_) => None,
+    }
 }
 
 // Map t from range [a, b] to range [c, d]
@@ -423,6 +1390,49 @@ fn remap(t: f32, a: f32, b: f32, c: f32, d: f32) -> f32 {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_split_params() {
+        let test_data = [
+            ("", vec![]),
+            ("0 255 0", vec!["0", "255", "0"]),
+            ("0,255,0,0.5", vec!["0", "255", "0", "0.5"]),
+            (" 0 255 0 / 50% ", vec!["0", "255", "0", "50%"]),
+            (" 0 , 255 , 0 , 50% ", vec!["0", "255", "0", "50%"]),
+            ("0/**/0 0", vec!["0", "0", "0"]),
+            ("0 /* , */ 0 0", vec!["0", "0", "0"]),
+            ("calc(1 + 2) 0 0", vec!["calc(1 + 2)", "0", "0"]),
+            (
+                "from rgb(0, 255, 0) r g b",
+                vec!["from", "rgb(0, 255, 0)", "r", "g", "b"],
+            ),
+            (
+                "from hsl(from red h s l) h s l",
+                vec!["from", "hsl(from red h s l)", "h", "s", "l"],
+            ),
+        ];
+        for (s, expected) in test_data {
+            let params = split_params(s).map(|p| p.args.as_slice().to_vec());
+            assert_eq!(params, Ok(expected));
+        }
+
+        let test_data = [
+            "0,0 0",
+            "0 0,0",
+            "0,0,0 / 1",
+            "0 0 / 0 1",
+            "0 / 0 / 0",
+            ",0 0 0",
+            "0 0 0,",
+            "0,,0,0",
+            "0 0 0 /",
+            "calc(1 0 0",
+            "0) 0 0",
+        ];
+        for s in test_data {
+            assert!(split_params(s).is_err(), "{}", s);
+        }
+    }
+
     #[test]
     fn test_parse_percent_or_float() {
         let test_data = [
@@ -438,8 +1448,9 @@ mod tests {
             ("1x", None),
         ];
         for (s, expected) in test_data {
-            assert_eq!(parse_percent_or_float(s), expected);
+            assert_eq!(parse_percent_or_float(s, &None), expected);
         }
+        assert!(parse_percent_or_float("none", &None).unwrap().0.is_nan());
     }
 
     #[test]
@@ -456,8 +1467,9 @@ mod tests {
             ("255x", None),
         ];
         for (s, expected) in test_data {
-            assert_eq!(parse_percent_or_255(s), expected);
+            assert_eq!(parse_percent_or_255(s, &None), expected);
         }
+        assert!(parse_percent_or_255("none", &None).unwrap().0.is_nan());
     }
 
     #[test]
@@ -477,8 +1489,9 @@ mod tests {
             ("rad", None),
         ];
         for (s, expected) in test_data {
-            assert_eq!(parse_angle(s), expected);
+            assert_eq!(parse_angle(s, &None), expected);
         }
+        assert!(parse_angle("none", &None).unwrap().is_nan());
     }
 
     #[cfg(feature = "named-colors")]
@@ -0,0 +1,188 @@
+use core::fmt;
+#[cfg(feature = "alloc")]
+use core::ops::Range;
+
+#[cfg(feature = "alloc")]
+use alloc::string::String;
+
+use super::ParseColorError;
+
+/// What the parser expected at the position of a [`ParseError`].
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Expected {
+    /// A number of function arguments, e.g. `"3 or 4"`, and how many were found.
+    Arguments {
+        count: &'static str,
+        found: usize,
+    },
+    /// A function argument, found a separator instead.
+    Argument,
+    Number,
+    Percentage,
+    NumberOrPercentage,
+    /// A hue, as a number or an angle.
+    NumberOrAngle,
+    /// 3, 4, 6 or 8 hex digits.
+    HexDigits,
+    /// A named color, hex color or color function.
+    Color,
+    /// A supported color function, e.g. `rgb` or `oklch`.
+    ColorFunction,
+    /// A color space name, e.g. `srgb` or `display-p3`.
+    ColorSpace,
+    /// A hue interpolation method, e.g. `shorter`.
+    HueInterpolation,
+    /// A percentage between 0% and 100% in `color-mix()`.
+    MixPercentage,
+    /// `color-mix()` percentages that don't add up to zero.
+    NonZeroPercentages,
+    /// A [`Context`](crate::Context) to resolve the color with.
+    Context,
+    /// A keyword, e.g. `in`.
+    Keyword(&'static str),
+    /// A color nested at most this many levels deep in other colors.
+    Nesting(usize),
+    Comma,
+    Whitespace,
+    /// `/` before the alpha component.
+    Slash,
+    CloseParen,
+    EndOfInput,
+}
+
+impl fmt::Display for Expected {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Self::Arguments { count, .. } => write!(f, "{} arguments", count),
+            Self::Argument => f.write_str("an argument"),
+            Self::Number => f.write_str("a number"),
+            Self::Percentage => f.write_str("a percentage"),
+            Self::NumberOrPercentage => f.write_str("a number or percentage"),
+            Self::NumberOrAngle => f.write_str("a number or angle"),
+            Self::HexDigits => f.write_str("3, 4, 6 or 8 hex digits"),
+            Self::Color => f.write_str("a color"),
+            Self::ColorFunction => f.write_str("a color function"),
+            Self::ColorSpace => f.write_str("a color space"),
+            Self::HueInterpolation => f.write_str("a hue interpolation method"),
+            Self::MixPercentage => f.write_str("a percentage between 0% and 100%"),
+            Self::NonZeroPercentages => f.write_str("percentages with a non-zero sum"),
+            Self::Context => f.write_str("a resolution context"),
+            Self::Keyword(s) => write!(f, "'{}'", s),
+            Self::Nesting(n) => write!(f, "at most {} levels of nested colors", n),
+            Self::Comma => f.write_str("','"),
+            Self::Whitespace => f.write_str("whitespace"),
+            Self::Slash => f.write_str("'/'"),
+            Self::CloseParen => f.write_str("')'"),
+            Self::EndOfInput => f.write_str("end of input"),
+        }
+    }
+}
+
+/// Detailed parse error, returned by [`parse_detailed`](crate::parse_detailed).
+///
+/// # Examples
+///
+/// ```
+/// use csscolorparser::{Expected, ParseColorError};
+///
+/// let err = csscolorparser::parse_detailed("rgb(0 0 0 0 0)").unwrap_err();
+///
+/// assert_eq!(err.kind(), ParseColorError::InvalidRgb);
+/// assert_eq!(err.span(), 4..13);
+/// assert_eq!(err.expected(), Expected::Arguments { count: "3 or 4", found: 5 });
+/// assert_eq!(
+///     err.to_string(),
+///     "invalid rgb format: expected 3 or 4 arguments, found 5 at 4..13"
+/// );
+/// ```
+#[cfg(feature = "alloc")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub(crate) kind: ParseColorError,
+    pub(crate) span: Range<usize>,
+    pub(crate) token: String,
+    pub(crate) expected: Expected,
+}
+
+#[cfg(feature = "alloc")]
+impl ParseError {
+    /// The kind of error, as returned by [`parse`](crate::parse).
+    pub fn kind(&self) -> ParseColorError {
+        self.kind
+    }
+
+    /// Byte range of the offending token in the input.
+    pub fn span(&self) -> Range<usize> {
+        self.span.clone()
+    }
+
+    /// The offending token, empty at the end of the input.
+    pub fn token(&self) -> &str {
+        &self.token
+    }
+
+    /// What was expected instead of the offending token.
+    pub fn expected(&self) -> Expected {
+        self.expected
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}: expected {}, found ", self.kind, self.expected)?;
+        match self.expected {
+            Expected::Arguments { found, .. } => write!(f, "{}", found)?,
+            _ if self.token.is_empty() => f.write_str("end of input")?,
+            _ => write!(f, "`{}`", self.token)?,
+        }
+        write!(f, " at {}..{}", self.span.start, self.span.end)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ParseError {}
+
+#[cfg(feature = "alloc")]
+impl From<ParseError> for ParseColorError {
+    fn from(err: ParseError) -> Self {
+        err.kind
+    }
+}
+
+// A parse error at `token`, which is a slice of the input.
+#[derive(Debug)]
+#[cfg_attr(not(feature = "alloc"), allow(dead_code))]
+pub(crate) struct Error<'a> {
+    pub(crate) kind: ParseColorError,
+    pub(crate) token: &'a str,
+    pub(crate) expected: Expected,
+}
+
+impl<'a> Error<'a> {
+    pub(crate) fn new(kind: ParseColorError, token: &'a str, expected: Expected) -> Self {
+        Self {
+            kind,
+            token,
+            expected,
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    pub(crate) fn into_parse_error(self, input: &str) -> ParseError {
+        let start = (self.token.as_ptr() as usize)
+            .checked_sub(input.as_ptr() as usize)
+            .filter(|&i| i + self.token.len() <= input.len());
+        let span = match start {
+            Some(i) => i..i + self.token.len(),
+            None => 0..input.len(),
+        };
+        ParseError {
+            kind: self.kind,
+            span,
+            token: String::from(self.token),
+            expected: self.expected,
+        }
+    }
+}
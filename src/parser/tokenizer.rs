@@ -0,0 +1,483 @@
+// CSS Syntax Level 3 tokenizer, limited to the tokens that can appear in a
+// color value. Comments are skipped.
+// https://www.w3.org/TR/css-syntax-3/#tokenization
+
+use core::ops::Deref;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum Token<'a> {
+    /// Raw identifier, may contain escapes.
+    Ident(&'a str),
+    /// Raw function name, the `(` is part of the token.
+    Function(&'a str),
+    /// Raw name after `#`, may contain escapes.
+    Hash(&'a str),
+    /// Raw contents of a string without the quotes.
+    String(&'a str),
+    BadString,
+    Number(f32),
+    Percentage(f32),
+    /// Value and raw unit.
+    Dimension(f32, &'a str),
+    Whitespace,
+    Comma,
+    OpenParen,
+    CloseParen,
+    Delim(char),
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct Tokenizer<'a> {
+    s: &'a str,
+    pos: usize,
+    numbers: bool,
+}
+
+impl<'a> Tokenizer<'a> {
+    pub(crate) fn new(s: &'a str) -> Self {
+        Self {
+            s,
+            pos: 0,
+            numbers: true,
+        }
+    }
+
+    /// Skips converting numbers, their value is `0`. For finding the
+    /// structure of the input, which is faster.
+    pub(crate) fn structure_only(mut self) -> Self {
+        self.numbers = false;
+        self
+    }
+
+    /// Byte offset of the next token, or of the comment before it.
+    pub(crate) fn position(&self) -> usize {
+        self.pos
+    }
+
+    pub(crate) fn peek(&self) -> Option<Token<'a>> {
+        self.clone().next()
+    }
+
+    /// Returns the next token and the byte offset it starts at.
+    pub(crate) fn next_spanned(&mut self) -> Option<(usize, Token<'a>)> {
+        self.skip_comments();
+        let start = self.pos;
+        self.next().map(|t| (start, t))
+    }
+
+    /// Skips whitespace and comments, returns whether there was whitespace.
+    pub(crate) fn skip_whitespace(&mut self) -> bool {
+        let mut skipped = false;
+        loop {
+            self.skip_comments();
+            match self.byte(0) {
+                Some(c) if is_whitespace(c) => {
+                    self.pos += 1;
+                    skipped = true;
+                }
+                _ => return skipped,
+            }
+        }
+    }
+
+    /// Whether only whitespace and comments are left.
+    pub(crate) fn is_exhausted(&self) -> bool {
+        let mut t = self.clone();
+        t.skip_whitespace();
+        t.next().is_none()
+    }
+
+    fn byte(&self, offset: usize) -> Option<u8> {
+        self.s.as_bytes().get(self.pos + offset).copied()
+    }
+
+    fn skip_comments(&mut self) {
+        while self.s[self.pos..].starts_with("/*") {
+            self.pos = match self.s[self.pos + 2..].find("*/") {
+                Some(i) => self.pos + 2 + i + 2,
+                None => self.s.len(),
+            };
+        }
+    }
+
+    fn is_valid_escape(&self, offset: usize) -> bool {
+        self.byte(offset) == Some(b'\\') && !matches!(self.byte(offset + 1), None | Some(b'\n'))
+    }
+
+    fn starts_ident(&self, offset: usize) -> bool {
+        match self.byte(offset) {
+            Some(b'-') => match self.byte(offset + 1) {
+                Some(c) if is_name_start(c) || c == b'-' => true,
+                _ => self.is_valid_escape(offset + 1),
+            },
+            Some(b'\\') => self.is_valid_escape(offset),
+            Some(c) => is_name_start(c),
+            None => false,
+        }
+    }
+
+    fn starts_number(&self) -> bool {
+        let digit_at = |i: usize| self.byte(i).is_some_and(|c| c.is_ascii_digit());
+        match self.byte(0) {
+            Some(b'+') | Some(b'-') => digit_at(1) || (self.byte(1) == Some(b'.') && digit_at(2)),
+            Some(b'.') => digit_at(1),
+            Some(c) => c.is_ascii_digit(),
+            None => false,
+        }
+    }
+
+    fn consume_digits(&mut self) {
+        while self.byte(0).is_some_and(|c| c.is_ascii_digit()) {
+            self.pos += 1;
+        }
+    }
+
+    fn consume_number(&mut self) -> f32 {
+        let start = self.pos;
+        if let Some(b'+') | Some(b'-') = self.byte(0) {
+            self.pos += 1;
+        }
+        self.consume_digits();
+        if self.byte(0) == Some(b'.') && self.byte(1).is_some_and(|c| c.is_ascii_digit()) {
+            self.pos += 1;
+            self.consume_digits();
+        }
+        if let Some(b'e') | Some(b'E') = self.byte(0) {
+            let sign = matches!(self.byte(1), Some(b'+') | Some(b'-')) as usize;
+            if self.byte(1 + sign).is_some_and(|c| c.is_ascii_digit()) {
+                self.pos += 1 + sign;
+                self.consume_digits();
+            }
+        }
+        if !self.numbers {
+            return 0.0;
+        }
+        // The CSS number grammar is a subset of what Rust accepts
+        self.s[start..self.pos].parse().unwrap_or(0.0)
+    }
+
+    fn consume_escape(&mut self) {
+        // The backslash
+        self.pos += 1;
+        if self.byte(0).is_some_and(|c| c.is_ascii_hexdigit()) {
+            let mut n = 0;
+            while n < 6 && self.byte(0).is_some_and(|c| c.is_ascii_hexdigit()) {
+                self.pos += 1;
+                n += 1;
+            }
+            self.consume_escape_whitespace();
+        } else if let Some(c) = self.s[self.pos..].chars().next() {
+            self.pos += c.len_utf8();
+        }
+    }
+
+    fn consume_escape_whitespace(&mut self) {
+        match self.byte(0) {
+            Some(b'\r') if self.byte(1) == Some(b'\n') => self.pos += 2,
+            Some(c) if is_whitespace(c) => self.pos += 1,
+            _ => {}
+        }
+    }
+
+    fn consume_name(&mut self) -> &'a str {
+        let start = self.pos;
+        loop {
+            match self.byte(0) {
+                Some(c) if is_name(c) => self.pos += 1,
+                Some(b'\\') if self.is_valid_escape(0) => self.consume_escape(),
+                _ => return &self.s[start..self.pos],
+            }
+        }
+    }
+
+    fn consume_string(&mut self, quote: u8) -> Token<'a> {
+        // The opening quote
+        self.pos += 1;
+        let start = self.pos;
+        loop {
+            match self.byte(0) {
+                None => return Token::String(&self.s[start..]),
+                Some(c) if c == quote => {
+                    self.pos += 1;
+                    return Token::String(&self.s[start..self.pos - 1]);
+                }
+                Some(b'\n') | Some(b'\r') | Some(b'\x0C') => return Token::BadString,
+                Some(b'\\') => match self.byte(1) {
+                    None => self.pos += 1,
+                    Some(b'\n') => self.pos += 2,
+                    _ => self.consume_escape(),
+                },
+                // Multibyte characters never contain ASCII bytes
+                Some(_) => self.pos += 1,
+            }
+        }
+    }
+}
+
+impl<'a> Iterator for Tokenizer<'a> {
+    type Item = Token<'a>;
+
+    fn next(&mut self) -> Option<Token<'a>> {
+        self.skip_comments();
+        let c = self.byte(0)?;
+
+        if is_whitespace(c) {
+            while self.byte(0).is_some_and(is_whitespace) {
+                self.pos += 1;
+            }
+            return Some(Token::Whitespace);
+        }
+
+        if self.starts_number() {
+            let t = self.consume_number();
+            if self.starts_ident(0) {
+                return Some(Token::Dimension(t, self.consume_name()));
+            }
+            if self.byte(0) == Some(b'%') {
+                self.pos += 1;
+                return Some(Token::Percentage(t));
+            }
+            return Some(Token::Number(t));
+        }
+
+        if self.starts_ident(0) {
+            let name = self.consume_name();
+            if self.byte(0) == Some(b'(') {
+                self.pos += 1;
+                return Some(Token::Function(name));
+            }
+            return Some(Token::Ident(name));
+        }
+
+        match c {
+            b'#' if self.byte(1).is_some_and(is_name) || self.is_valid_escape(1) => {
+                self.pos += 1;
+                Some(Token::Hash(self.consume_name()))
+            }
+            b'"' | b'\'' => Some(self.consume_string(c)),
+            b',' => {
+                self.pos += 1;
+                Some(Token::Comma)
+            }
+            b'(' => {
+                self.pos += 1;
+                Some(Token::OpenParen)
+            }
+            b')' => {
+                self.pos += 1;
+                Some(Token::CloseParen)
+            }
+            _ => {
+                // Always ASCII, anything else starts an identifier
+                self.pos += 1;
+                Some(Token::Delim(c as char))
+            }
+        }
+    }
+}
+
+fn is_whitespace(c: u8) -> bool {
+    matches!(c, b' ' | b'\t' | b'\n' | b'\r' | b'\x0C')
+}
+
+fn is_name_start(c: u8) -> bool {
+    c.is_ascii_alphabetic() || c == b'_' || !c.is_ascii()
+}
+
+fn is_name(c: u8) -> bool {
+    is_name_start(c) || c.is_ascii_digit() || c == b'-'
+}
+
+/// Identifier with escapes resolved and ASCII letters in lowercase, CSS
+/// keywords are ASCII case-insensitive.
+pub(crate) fn name(raw: &str) -> Name<'_> {
+    if !raw.bytes().any(|c| c == b'\\' || c.is_ascii_uppercase()) {
+        return Name::Borrowed(raw);
+    }
+
+    let mut buf = [0; NAME_CAPACITY];
+    let mut len = 0;
+    let mut chars = raw.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        let c = if c != '\\' {
+            c
+        } else {
+            decode_escape(&mut chars)
+        };
+        let c = c.to_ascii_lowercase();
+        if len + c.len_utf8() > NAME_CAPACITY {
+            // Longer than any keyword
+            return Name::Borrowed(raw);
+        }
+        len += c.encode_utf8(&mut buf[len..]).len();
+    }
+
+    Name::Inline(buf, len)
+}
+
+// The code point of an escape, after the backslash.
+fn decode_escape(chars: &mut core::iter::Peekable<core::str::Chars<'_>>) -> char {
+    let mut code = 0;
+    let mut n = 0;
+    while n < 6 {
+        match chars.peek().and_then(|c| c.to_digit(16)) {
+            Some(d) => {
+                code = code * 16 + d;
+                n += 1;
+                chars.next();
+            }
+            None => break,
+        }
+    }
+    if n == 0 {
+        return chars.next().unwrap_or('\u{FFFD}');
+    }
+    match chars.peek() {
+        Some('\r') => {
+            chars.next();
+            if chars.peek() == Some(&'\n') {
+                chars.next();
+            }
+        }
+        Some(' ') | Some('\t') | Some('\n') | Some('\x0C') => {
+            chars.next();
+        }
+        _ => {}
+    }
+    match char::from_u32(code) {
+        Some('\0') | None => '\u{FFFD}',
+        Some(c) => c,
+    }
+}
+
+const NAME_CAPACITY: usize = 32;
+
+/// A decoded identifier, kept on the stack. Names that don't fit are kept as
+/// written.
+pub(crate) enum Name<'a> {
+    Borrowed(&'a str),
+    Inline([u8; NAME_CAPACITY], usize),
+}
+
+impl Deref for Name<'_> {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        match self {
+            Self::Borrowed(s) => s,
+            // Only whole characters are written to the buffer
+            Self::Inline(buf, len) => core::str::from_utf8(&buf[..*len]).unwrap_or_default(),
+        }
+    }
+}
+
+impl PartialEq<&str> for Name<'_> {
+    fn eq(&self, other: &&str) -> bool {
+        &**self == *other
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tokenize(s: &str) -> Vec<Token<'_>> {
+        Tokenizer::new(s).collect()
+    }
+
+    #[test]
+    fn test_tokenizer() {
+        use Token::*;
+
+        let test_data = [
+            ("", vec![]),
+            ("red", vec![Ident("red")]),
+            ("#f00", vec![Hash("f00")]),
+            ("#", vec![Delim('#')]),
+            (
+                "rgb(0, 1.5 / 50%)",
+                vec![
+                    Function("rgb"),
+                    Number(0.0),
+                    Comma,
+                    Whitespace,
+                    Number(1.5),
+                    Whitespace,
+                    Delim('/'),
+                    Whitespace,
+                    Percentage(50.0),
+                    CloseParen,
+                ],
+            ),
+            ("0/**/0", vec![Number(0.0), Number(0.0)]),
+            ("/* a */ 1 /* b", vec![Whitespace, Number(1.0), Whitespace]),
+            ("90deg", vec![Dimension(90.0, "deg")]),
+            ("1e3", vec![Number(1000.0)]),
+            ("1e", vec![Dimension(1.0, "e")]),
+            ("1e+", vec![Dimension(1.0, "e"), Delim('+')]),
+            ("-.5e-1", vec![Number(-0.05)]),
+            ("+5", vec![Number(5.0)]),
+            ("5.", vec![Number(5.0), Delim('.')]),
+            ("1 -2", vec![Number(1.0), Whitespace, Number(-2.0)]),
+            (
+                "1 - 2",
+                vec![Number(1.0), Whitespace, Delim('-'), Whitespace, Number(2.0)],
+            ),
+            ("-infinity", vec![Ident("-infinity")]),
+            ("inf", vec![Ident("inf")]),
+            ("nan", vec![Ident("nan")]),
+            ("--x", vec![Ident("--x")]),
+            ("r\\67 b(", vec![Function("r\\67 b")]),
+            ("'a)b'", vec![String("a)b")]),
+            (
+                "\"a\nb\"",
+                vec![BadString, Whitespace, Ident("b"), String("")],
+            ),
+            ("\u{1F602}x", vec![Ident("\u{1F602}x")]),
+            ("1\u{e9}", vec![Dimension(1.0, "\u{e9}")]),
+            ("\\", vec![Delim('\\')]),
+        ];
+
+        for (s, expected) in test_data {
+            assert_eq!(tokenize(s), expected, "{}", s);
+        }
+
+        let tokens: Vec<_> = Tokenizer::new("1.5 2% 3deg").structure_only().collect();
+        assert_eq!(
+            tokens,
+            vec![
+                Number(0.0),
+                Whitespace,
+                Percentage(0.0),
+                Whitespace,
+                Dimension(0.0, "deg")
+            ]
+        );
+    }
+
+    #[test]
+    fn test_name() {
+        let test_data = [
+            ("red", "red"),
+            ("RED", "red"),
+            ("r\\65 d", "red"),
+            ("r\\000065d", "red"),
+            ("\\52 ED", "red"),
+            ("r\\ed", "r\u{ed}"),
+            ("\\-x", "-x"),
+            ("\\0", "\u{FFFD}"),
+            ("\\110000", "\u{FFFD}"),
+            ("\u{c9}", "\u{c9}"),
+            ("\\44 ark\\53 lateBlue", "darkslateblue"),
+        ];
+
+        for (raw, expected) in test_data {
+            assert_eq!(&*name(raw), expected);
+        }
+
+        // Names that don't fit in the buffer are kept as written
+        let raw = "X".repeat(NAME_CAPACITY + 1);
+        assert_eq!(&*name(&raw), raw);
+    }
+}
@@ -0,0 +1,293 @@
+// Substitution of `var()` references, CSS Custom Properties Level 1.
+// https://www.w3.org/TR/css-variables-1/#using-variables
+
+use alloc::borrow::Cow;
+use alloc::collections::BTreeMap;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::borrow::Borrow;
+use core::fmt;
+
+use super::tokenizer::{name, Token, Tokenizer};
+use super::{ParseColorError, Parser};
+use crate::Color;
+
+/// Values of the CSS custom properties used by [`parse_with_vars()`]
+///
+/// Implemented for closures, `HashMap` and `BTreeMap` with string keys and
+/// values.
+pub trait VarResolver {
+    /// The value of the custom property `name`, including the leading `--`
+    fn resolve(&self, name: &str) -> Option<Cow<'_, str>>;
+}
+
+impl<F> VarResolver for F
+where
+    F: Fn(&str) -> Option<String>,
+{
+    fn resolve(&self, name: &str) -> Option<Cow<'_, str>> {
+        self(name).map(Cow::Owned)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<K, V, S> VarResolver for std::collections::HashMap<K, V, S>
+where
+    K: Borrow<str> + core::hash::Hash + Eq,
+    V: AsRef<str>,
+    S: core::hash::BuildHasher,
+{
+    fn resolve(&self, name: &str) -> Option<Cow<'_, str>> {
+        self.get(name).map(|v| Cow::Borrowed(v.as_ref()))
+    }
+}
+
+impl<K, V> VarResolver for BTreeMap<K, V>
+where
+    K: Borrow<str> + Ord,
+    V: AsRef<str>,
+{
+    fn resolve(&self, name: &str) -> Option<Cow<'_, str>> {
+        self.get(name).map(|v| Cow::Borrowed(v.as_ref()))
+    }
+}
+
+/// Error of [`parse_with_vars()`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VarError {
+    /// A `var()` without a custom property name, e.g. `var(red)`.
+    InvalidVar,
+    /// The custom property is not defined, and the `var()` has no fallback.
+    Undefined(String),
+    /// The custom property references itself, directly or through other
+    /// custom properties, and the `var()` has no fallback.
+    Cycle(String),
+    /// The substitution exceeds 64 KiB or 1024 `var()` references, e.g.
+    /// because each custom property references the next one twice.
+    TooLong,
+    /// The color is invalid after substitution.
+    Parse(ParseColorError),
+}
+
+impl fmt::Display for VarError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::InvalidVar => f.write_str("invalid var() format"),
+            Self::Undefined(name) => write!(f, "undefined variable {}", name),
+            Self::Cycle(name) => write!(f, "cyclic reference in variable {}", name),
+            Self::TooLong => f.write_str("substituted value is too long"),
+            Self::Parse(e) => e.fmt(f),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for VarError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Parse(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<ParseColorError> for VarError {
+    fn from(e: ParseColorError) -> Self {
+        Self::Parse(e)
+    }
+}
+
+/// Parse CSS color string, like [`parse`](crate::parse), substituting the
+/// `var()` references first
+///
+/// A `var()` is replaced by the value of the custom property, which may
+/// contain `var()` references itself. The fallback is used if the custom
+/// property is not defined or is part of a reference cycle.
+///
+/// # Examples
+///
+/// ```
+/// use std::collections::HashMap;
+/// use csscolorparser::{parse_with_vars, VarError};
+///
+/// let vars = HashMap::from([
+///     ("--r", "255"),
+///     ("--brand", "rgb(var(--r) 170 0)"),
+///     ("--loop", "var(--loop)"),
+/// ]);
+///
+/// let c = parse_with_vars("var(--brand)", &vars).unwrap();
+/// assert_eq!(c.to_rgba8(), [255, 170, 0, 255]);
+///
+/// let c = parse_with_vars("var(--brand-500, #0af)", &vars).unwrap();
+/// assert_eq!(c.to_rgba8(), [0, 170, 255, 255]);
+///
+/// let err = parse_with_vars("rgb(var(--r) var(--g) 0)", &vars).unwrap_err();
+/// assert_eq!(err, VarError::Undefined("--g".into()));
+///
+/// let err = parse_with_vars("var(--loop)", &vars).unwrap_err();
+/// assert_eq!(err, VarError::Cycle("--loop".into()));
+/// ```
+pub fn parse_with_vars(s: &str, vars: &dyn VarResolver) -> Result<Color, VarError> {
+    Parser::new().parse_with_vars(s, vars)
+}
+
+impl Parser<'_> {
+    /// Parse CSS color string with `var()` references, see [`parse_with_vars()`]
+    pub fn parse_with_vars(&self, s: &str, vars: &dyn VarResolver) -> Result<Color, VarError> {
+        let mut out = String::with_capacity(s.len());
+        Substitution {
+            vars,
+            stack: Vec::new(),
+            count: 0,
+        }
+        .substitute(s, &mut out)?;
+        Ok(self.parse(&out)?)
+    }
+}
+
+// Limits of the substituted value, it can grow exponentially with the depth
+// of the references.
+const MAX_LENGTH: usize = 64 * 1024;
+const MAX_VARS: usize = 1024;
+
+struct Substitution<'v> {
+    vars: &'v dyn VarResolver,
+    // Names of the custom properties being substituted
+    stack: Vec<String>,
+    // Number of `var()` references substituted so far
+    count: usize,
+}
+
+impl Substitution<'_> {
+    // Append `s` to `out` with all `var()` references replaced.
+    fn substitute(&mut self, s: &str, out: &mut String) -> Result<(), VarError> {
+        let mut tokens = Tokenizer::new(s).structure_only();
+        let mut copied = 0;
+
+        while let Some((start, token)) = tokens.next_spanned() {
+            let fname = match token {
+                Token::Function(fname) => fname,
+                _ => continue,
+            };
+            if &*name(fname) != "var" {
+                continue;
+            }
+            let body_start = tokens.position();
+            let body_end = skip_block(&mut tokens).ok_or(VarError::InvalidVar)?;
+
+            out.push_str(&s[copied..start]);
+            // Keep the value apart from the surrounding tokens
+            out.push(' ');
+            self.var(&s[body_start..body_end], out)?;
+            out.push(' ');
+            copied = tokens.position();
+        }
+
+        out.push_str(&s[copied..]);
+        if out.len() > MAX_LENGTH {
+            return Err(VarError::TooLong);
+        }
+        Ok(())
+    }
+
+    // var( <custom-property-name> , <declaration-value>? )
+    fn var(&mut self, body: &str, out: &mut String) -> Result<(), VarError> {
+        self.count += 1;
+        if self.count > MAX_VARS {
+            return Err(VarError::TooLong);
+        }
+        let mut tokens = Tokenizer::new(body).structure_only();
+        tokens.skip_whitespace();
+        let var_name = match tokens.next() {
+            Some(Token::Ident(id)) if id.starts_with("--") => id,
+            _ => return Err(VarError::InvalidVar),
+        };
+        tokens.skip_whitespace();
+        let fallback = match tokens.next() {
+            None => None,
+            Some(Token::Comma) => Some(body[tokens.position()..].trim()),
+            Some(_) => return Err(VarError::InvalidVar),
+        };
+
+        let len = out.len();
+        match (self.resolve(var_name, out), fallback) {
+            (Ok(()), _) => Ok(()),
+            // The custom properties of a cycle are invalid even if they
+            // reference each other with a fallback
+            (Err(VarError::Cycle(n)), _) if self.stack.contains(&n) => Err(VarError::Cycle(n)),
+            (Err(VarError::Undefined(_) | VarError::Cycle(_)), Some(fallback)) => {
+                out.truncate(len);
+                self.substitute(fallback, out)
+            }
+            (Err(e), _) => Err(e),
+        }
+    }
+
+    fn resolve(&mut self, var_name: &str, out: &mut String) -> Result<(), VarError> {
+        if self.stack.iter().any(|n| n == var_name) {
+            return Err(VarError::Cycle(var_name.into()));
+        }
+        let vars = self.vars;
+        let value = vars
+            .resolve(var_name)
+            .ok_or_else(|| VarError::Undefined(var_name.into()))?;
+
+        self.stack.push(var_name.into());
+        let res = self.substitute(&value, out);
+        self.stack.pop();
+        res
+    }
+}
+
+// Skip to the end of the block `tokens` is in, returns the position of the
+// closing parenthesis.
+fn skip_block(tokens: &mut Tokenizer<'_>) -> Option<usize> {
+    let mut depth = 0;
+    loop {
+        match tokens.next_spanned()? {
+            (_, Token::Function(_)) | (_, Token::OpenParen) => depth += 1,
+            (pos, Token::CloseParen) if depth == 0 => return Some(pos),
+            (_, Token::CloseParen) => depth -= 1,
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_substitute() {
+        let vars = BTreeMap::from([
+            ("--a", "1"),
+            ("--b", "var(--a) var(--a)"),
+            ("--C", "3"),
+            ("--cycle", "var(--cycle, 0)"),
+        ]);
+
+        let test_data = [
+            ("", ""),
+            ("var(--a)", " 1 "),
+            ("VAR( --a )", " 1 "),
+            ("f(var(--b))", "f(  1   1  )"),
+            ("var(--C)", " 3 "),
+            ("var(--c, 0)", " 0 "),
+            ("var(--c,)", "  "),
+            ("var(--c, var(--a))", "  1  "),
+            ("var(--cycle, 2)", " 2 "),
+            ("'var(--a)'", "'var(--a)'"),
+        ];
+        for (s, expected) in test_data {
+            let mut out = String::new();
+            let mut sub = Substitution {
+                vars: &vars,
+                stack: Vec::new(),
+                count: 0,
+            };
+            sub.substitute(s, &mut out).unwrap();
+            assert_eq!(out, expected, "{}", s);
+        }
+    }
+}
@@ -0,0 +1,111 @@
+// X11 color names and the `XParseColor` syntaxes.
+// https://www.x.org/releases/current/doc/libX11/libX11/libX11.html#Color_Strings
+
+use super::Components;
+use crate::colorspace::{lab_to_xyz, lightness_to_y, D65_WHITE as WHITE};
+use crate::ColorSpace;
+
+pub use super::x11_colors::X11_COLORS;
+
+// ASCII case-insensitive name lookup, the names may contain spaces.
+pub(crate) fn named(name: &str) -> Option<[u8; 3]> {
+    let mut buf = [0; 32];
+    let buf = buf.get_mut(..name.len())?;
+    buf.copy_from_slice(name.as_bytes());
+    buf.make_ascii_lowercase();
+    X11_COLORS.get(core::str::from_utf8(buf).ok()?).copied()
+}
+
+// `#rrrgggbbb` and `#rrrrggggbbbb`, the 12 and 24 bit forms are CSS hex colors.
+pub(crate) fn parse_hex(hex: &str) -> Option<Components> {
+    if hex.len() != 9 && hex.len() != 12 {
+        return None;
+    }
+    let n = hex.len() / 3;
+    let channel = |i: usize| hex_channel(hex.get(i * n..(i + 1) * n)?);
+    Some((
+        ColorSpace::Srgb,
+        [channel(0)?, channel(1)?, channel(2)?, 1.0],
+    ))
+}
+
+// `<prefix>:<a>/<b>/<c>`
+pub(crate) fn parse(s: &str) -> Option<Components> {
+    let (prefix, values) = s.split_once(':')?;
+    let mut values = values.split('/');
+    let mut next = |f: fn(&str) -> Option<f32>| values.next().and_then(f);
+    let [a, b, c] = if prefix.eq_ignore_ascii_case("rgb") {
+        [next(hex_channel)?, next(hex_channel)?, next(hex_channel)?]
+    } else {
+        [next(float)?, next(float)?, next(float)?]
+    };
+    if values.next().is_some() {
+        return None;
+    }
+
+    let is = |name: &str| prefix.eq_ignore_ascii_case(name);
+    let xyz = if is("rgb") {
+        return Some((ColorSpace::Srgb, [a, b, c, 1.0]));
+    } else if is("rgbi") {
+        let rgb = [a, b, c];
+        if !rgb.iter().all(|t| (0.0..=1.0).contains(t)) {
+            return None;
+        }
+        return Some((ColorSpace::Srgb, [a, b, c, 1.0]));
+    } else if is("ciexyz") {
+        [a, b, c]
+    } else if is("ciexyy") {
+        xyy_to_xyz(a, b, c)
+    } else if is("cieuvy") {
+        uvy_to_xyz(a, b, c)
+    } else if is("cielab") {
+        lab_to_xyz([a, b, c], WHITE)
+    } else if is("cieluv") {
+        luv_to_xyz(a, b, c)
+    } else {
+        return None;
+    };
+    Some((ColorSpace::XyzD65, [xyz[0], xyz[1], xyz[2], 1.0]))
+}
+
+// 1 to 4 hex digits, scaled to [0..1]
+fn hex_channel(s: &str) -> Option<f32> {
+    if s.is_empty() || s.len() > 4 || !s.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return None;
+    }
+    let max = (1u32 << (4 * s.len())) - 1;
+    Some(u32::from_str_radix(s, 16).ok()? as f32 / max as f32)
+}
+
+fn float(s: &str) -> Option<f32> {
+    s.parse::<f32>().ok().filter(|t| t.is_finite())
+}
+
+fn xyy_to_xyz(x: f32, y: f32, yy: f32) -> [f32; 3] {
+    if y == 0.0 {
+        return [0.0, 0.0, 0.0];
+    }
+    [x * yy / y, yy, (1.0 - x - y) * yy / y]
+}
+
+// CIE 1976 u'v'
+fn uvy_to_xyz(u: f32, v: f32, y: f32) -> [f32; 3] {
+    if v == 0.0 {
+        return [0.0, 0.0, 0.0];
+    }
+    [
+        y * 9.0 * u / (4.0 * v),
+        y,
+        y * (12.0 - 3.0 * u - 20.0 * v) / (4.0 * v),
+    ]
+}
+
+fn luv_to_xyz(l: f32, u: f32, v: f32) -> [f32; 3] {
+    if l == 0.0 {
+        return [0.0, 0.0, 0.0];
+    }
+    let [x, y, z] = WHITE;
+    let d = x + 15.0 * y + 3.0 * z;
+    let (un, vn) = (4.0 * x / d, 9.0 * y / d);
+    uvy_to_xyz(u / (13.0 * l) + un, v / (13.0 * l) + vn, lightness_to_y(l))
+}
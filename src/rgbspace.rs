@@ -0,0 +1,249 @@
+// RGB color spaces defined by their primaries, white point and transfer
+// function, e.g. of cameras and displays.
+
+use crate::colorspace::{inverse3, mul3, mul33, Mat3, XYZ_D65_TO_SRGB};
+#[cfg(not(any(feature = "std", test)))]
+use crate::math::FloatExt;
+use crate::{ChromaticAdaptation, Color, WhitePoint};
+
+/// Transfer function of an [`RgbSpace`], from encoded to linear values
+///
+/// Like the CSS predefined color spaces, negative values are converted by
+/// symmetry.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum TransferFunction {
+    /// Linear values.
+    #[default]
+    Linear,
+    /// Pure power law, `x^gamma`.
+    Gamma(f32),
+    /// The piecewise sRGB curve, also of Display P3.
+    Srgb,
+    /// [BT.1886](https://www.itu.int/rec/R-REC-BT.1886), the display curve of
+    /// Rec. 709 and Rec. 2020, with the luminance of white and black in
+    /// cd/m². Linear values are relative to white.
+    Bt1886 { white: f32, black: f32 },
+    /// ICC parametric curve: `(a * x + b)^g + e` if `x >= d`, otherwise
+    /// `c * x + f`. The other ICC parametric curve types are special cases,
+    /// e.g. `Parametric { g, a: 1.0, b: 0.0, c: 0.0, d: 0.0, e: 0.0, f: 0.0 }`
+    /// is the gamma `g`.
+    Parametric {
+        g: f32,
+        a: f32,
+        b: f32,
+        c: f32,
+        d: f32,
+        e: f32,
+        f: f32,
+    },
+}
+
+impl TransferFunction {
+    /// Decode `x` to a linear value.
+    pub fn to_linear(self, x: f32) -> f32 {
+        let t = x.abs();
+        let y = match self {
+            Self::Linear => t,
+            Self::Gamma(gamma) => t.powf(gamma),
+            Self::Srgb => {
+                if t > 0.04045 {
+                    ((t + 0.055) / 1.055).powf(2.4)
+                } else {
+                    t / 12.92
+                }
+            }
+            Self::Bt1886 { white, black } => {
+                let (a, b) = bt1886(white, black);
+                a * (t + b).max(0.0).powf(2.4) / white
+            }
+            Self::Parametric {
+                g,
+                a,
+                b,
+                c,
+                d,
+                e,
+                f,
+            } => {
+                if t >= d {
+                    (a * t + b).max(0.0).powf(g) + e
+                } else {
+                    c * t + f
+                }
+            }
+        };
+        x.signum() * y
+    }
+
+    /// Encode the linear value `y`, the inverse of
+    /// [`to_linear()`](Self::to_linear).
+    pub fn from_linear(self, y: f32) -> f32 {
+        let t = y.abs();
+        let x = match self {
+            Self::Linear => t,
+            Self::Gamma(gamma) => t.powf(1.0 / gamma),
+            Self::Srgb => {
+                if t > 0.0031308 {
+                    1.055 * t.powf(1.0 / 2.4) - 0.055
+                } else {
+                    12.92 * t
+                }
+            }
+            Self::Bt1886 { white, black } => {
+                let (a, b) = bt1886(white, black);
+                (t * white / a).powf(1.0 / 2.4) - b
+            }
+            Self::Parametric {
+                g,
+                a,
+                b,
+                c,
+                d,
+                e,
+                f,
+            } => {
+                if t >= (a * d + b).max(0.0).powf(g) + e {
+                    ((t - e).max(0.0).powf(1.0 / g) - b) / a
+                } else if c != 0.0 {
+                    (t - f) / c
+                } else {
+                    d
+                }
+            }
+        };
+        y.signum() * x
+    }
+}
+
+// The gain and black lift of BT.1886
+fn bt1886(white: f32, black: f32) -> (f32, f32) {
+    let w = white.powf(1.0 / 2.4);
+    let b = black.powf(1.0 / 2.4);
+    ((w - b).powf(2.4), b / (w - b))
+}
+
+/// RGB color space with the CIE xy chromaticities of its primaries, a white
+/// point and a [`TransferFunction`]
+///
+/// The white point of [`Color`] is D65, colors of other white points are
+/// adapted with [`RgbSpace::adaptation()`], Bradford by default.
+///
+/// # Examples
+///
+/// ```
+/// use csscolorparser::{Color, RgbSpace, TransferFunction, WhitePoint};
+///
+/// // Adobe RGB (1998)
+/// let space = RgbSpace::new([[0.64, 0.33], [0.21, 0.71], [0.15, 0.06]], WhitePoint::D65)
+///     .transfer(TransferFunction::Gamma(563.0 / 256.0));
+///
+/// let c = space.to_color([0.5, 0.25, 0.75, 1.0]);
+/// assert_eq!(c.to_rgba8(), Color::from_a98_rgba(0.5, 0.25, 0.75, 1.0).to_rgba8());
+///
+/// let [r, g, b, a] = space.from_color(&Color::new(1.0, 1.0, 1.0, 0.5));
+/// assert!((r - 1.0).abs() < 1e-4 && (g - 1.0).abs() < 1e-4 && (b - 1.0).abs() < 1e-4);
+/// assert_eq!(a, 0.5);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RgbSpace {
+    primaries: [[f32; 2]; 3],
+    white: WhitePoint,
+    transfer: TransferFunction,
+    adaptation: ChromaticAdaptation,
+    to_xyz: Mat3,
+    // Linear RGB to and from linear sRGB
+    to_srgb: Mat3,
+    from_srgb: Mat3,
+}
+
+impl RgbSpace {
+    /// Color space with the xy chromaticities of the red, green and blue
+    /// primaries and the `white` point, with linear values.
+    pub fn new(primaries: [[f32; 2]; 3], white: WhitePoint) -> Self {
+        let [[xr, yr], [xg, yg], [xb, yb]] = primaries;
+        let p = [
+            [xr / yr, xg / yg, xb / yb],
+            [1.0, 1.0, 1.0],
+            [
+                (1.0 - xr - yr) / yr,
+                (1.0 - xg - yg) / yg,
+                (1.0 - xb - yb) / yb,
+            ],
+        ];
+        // Scale the primaries so that they add up to the white point
+        let [sr, sg, sb] = mul3(&inverse3(&p), white.xyz());
+        let to_xyz = [
+            [p[0][0] * sr, p[0][1] * sg, p[0][2] * sb],
+            [p[1][0] * sr, p[1][1] * sg, p[1][2] * sb],
+            [p[2][0] * sr, p[2][1] * sg, p[2][2] * sb],
+        ];
+        Self {
+            primaries,
+            white,
+            transfer: TransferFunction::Linear,
+            adaptation: ChromaticAdaptation::Bradford,
+            to_xyz,
+            to_srgb: [[0.0; 3]; 3],
+            from_srgb: [[0.0; 3]; 3],
+        }
+        .update()
+    }
+
+    /// Transfer function of the values, linear by default
+    pub fn transfer(mut self, transfer: TransferFunction) -> Self {
+        self.transfer = transfer;
+        self
+    }
+
+    /// Chromatic adaptation between the white point and the D65 white point
+    /// of [`Color`], Bradford by default
+    pub fn adaptation(mut self, adaptation: ChromaticAdaptation) -> Self {
+        self.adaptation = adaptation;
+        self.update()
+    }
+
+    fn update(mut self) -> Self {
+        let adapt = self.adaptation.matrix(self.white, WhitePoint::D65);
+        self.to_srgb = mul33(&XYZ_D65_TO_SRGB, &mul33(&adapt, &self.to_xyz));
+        self.from_srgb = inverse3(&self.to_srgb);
+        self
+    }
+
+    /// The xy chromaticities of the red, green and blue primaries
+    pub fn primaries(&self) -> [[f32; 2]; 3] {
+        self.primaries
+    }
+
+    /// The white point
+    pub fn white(&self) -> WhitePoint {
+        self.white
+    }
+
+    /// The matrix from linear RGB to CIE XYZ relative to the white point,
+    /// to multiply column vectors
+    pub fn to_xyz_matrix(&self) -> [[f32; 3]; 3] {
+        self.to_xyz
+    }
+
+    /// The matrix from CIE XYZ relative to the white point to linear RGB,
+    /// to multiply column vectors
+    pub fn from_xyz_matrix(&self) -> [[f32; 3]; 3] {
+        inverse3(&self.to_xyz)
+    }
+
+    /// Convert `[r, g, b, alpha]` in this color space to [`Color`]
+    pub fn to_color(&self, [r, g, b, alpha]: [f32; 4]) -> Color {
+        let rgb = [r, g, b].map(|t| self.transfer.to_linear(t));
+        let [r, g, b] = mul3(&self.to_srgb, rgb);
+        Color::from_linear_rgba(r, g, b, alpha)
+    }
+
+    /// Returns: `[r, g, b, alpha]`
+    ///
+    /// * `c` converted to this color space, not clamped to its gamut
+    pub fn from_color(&self, c: &Color) -> [f32; 4] {
+        let [r, g, b, alpha] = c.to_linear_rgba();
+        let [r, g, b] = mul3(&self.from_srgb, [r, g, b]).map(|t| self.transfer.from_linear(t));
+        [r, g, b, alpha]
+    }
+}
@@ -0,0 +1,99 @@
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+
+use csscolorparser::{parse, parse_html_legacy, ColorScheme, Context, Parser};
+
+// Counts the allocations of the current thread, tests run in parallel.
+struct Counter;
+
+thread_local! {
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for Counter {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.with(|n| n.set(n.get() + 1));
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: Counter = Counter;
+
+fn allocations(f: impl FnOnce()) -> usize {
+    let before = ALLOCATIONS.with(Cell::get);
+    f();
+    ALLOCATIONS.with(Cell::get) - before
+}
+
+#[test]
+fn parse_without_allocation() {
+    assert_eq!(allocations(|| drop(String::from("red"))), 1);
+
+    let test_data = [
+        "transparent",
+        "#ff00ff64",
+        "#ABCD",
+        "ff00ff64",
+        "rgb(247,179,99)",
+        " RGB ( 255 , 0 , 0 ) ",
+        "rgb(0 255 127 / 75%)",
+        "hsl(180,0.5,0.27)",
+        "HSL(120DEG 100% 50%)",
+        "hwb(480deg 0% 0% / 100%)",
+        "hsv(120 100% 100%)",
+        "oklab(0.62796 0.22486 0.12585)",
+        "oklch(0.86644, 0.29483, 142.49535)",
+        "color(display-p3 0.91749 0.20029 0.13856)",
+        "rgb(from #3366cc b g r)",
+        "hsl(from red calc(h + 180) s l)",
+        "rgb(calc(255 * 0.5) min(0, 10) max(0, 1, 2))",
+        "color-mix(in oklch longer hue, red 30%, blue)",
+        "rgb(0 none 0 / none)",
+        "rgb(/* comment */ 0 0 0)",
+        // Invalid
+        "",
+        "#78afzd",
+        "rgb(255,0,0",
+        "rgb(0 0 0 0 0 0 0 0 0 0)",
+        "cmyk(0 0 0 0)",
+    ];
+
+    for s in test_data {
+        assert_eq!(allocations(|| drop(parse(s))), 0, "{}", s);
+        assert_eq!(allocations(|| drop(Parser::strict().parse(s))), 0, "{}", s);
+    }
+
+    let ctx = Context::new(ColorScheme::Dark);
+    for s in [
+        "currentcolor",
+        "Canvas",
+        "light-dark(#fff, rgb(from currentcolor r g b))",
+    ] {
+        assert_eq!(allocations(|| drop(parse(s))), 0, "{}", s);
+        let parser = Parser::new();
+        assert_eq!(
+            allocations(|| drop(parser.parse_with_context(s, &ctx))),
+            0,
+            "{}",
+            s
+        );
+    }
+
+    for s in ["chucknorris", "#fff", "  #ff8000 ", "red", "\u{1F600}f"] {
+        assert_eq!(allocations(|| drop(parse_html_legacy(s))), 0, "{}", s);
+    }
+
+    #[cfg(feature = "named-colors")]
+    {
+        let test_data = ["red", "RebeccaPurple", "LIGHTGOLDENRODYELLOW", "r\\65 d"];
+
+        for s in test_data {
+            assert_eq!(allocations(|| drop(parse(s))), 0, "{}", s);
+        }
+    }
+}
@@ -1,4 +1,4 @@
-use csscolorparser::Color;
+use csscolorparser::{Color, ColorSpace, HueInterpolation};
 use std::convert::TryFrom;
 
 #[test]
@@ -43,20 +43,17 @@ fn basic() {
     assert_eq!(c.to_hsla(), [0.0, 0.0, 0.5, 1.0]);
     assert_eq!(c.to_hwba(), [0.0, 0.5, 0.5, 1.0]);
 
-    #[cfg(feature = "lab")]
-    {
-        let c = Color::from_laba(0.0, 0.0, 0.0, 1.0);
-        assert_eq!(c.to_rgba8(), [0, 0, 0, 255]);
+    let c = Color::from_laba(0.0, 0.0, 0.0, 1.0);
+    assert_eq!(c.to_rgba8(), [0, 0, 0, 255]);
 
-        let c = Color::from_laba(100.0, 0.0, 0.0, 1.0);
-        assert_eq!(c.to_rgba8(), [255, 255, 255, 255]);
+    let c = Color::from_laba(100.0, 0.0, 0.0, 1.0);
+    assert_eq!(c.to_rgba8(), [255, 255, 255, 255]);
 
-        let c = Color::from_lcha(0.0, 0.0, 0.0, 1.0);
-        assert_eq!(c.to_rgba8(), [0, 0, 0, 255]);
+    let c = Color::from_lcha(0.0, 0.0, 0.0, 1.0);
+    assert_eq!(c.to_rgba8(), [0, 0, 0, 255]);
 
-        let c = Color::from_lcha(100.0, 0.0, 0.0, 1.0);
-        assert_eq!(c.to_rgba8(), [255, 255, 255, 255]);
-    }
+    let c = Color::from_lcha(100.0, 0.0, 0.0, 1.0);
+    assert_eq!(c.to_rgba8(), [255, 255, 255, 255]);
 
     assert_eq!(Color::default().to_rgba8(), [0, 0, 0, 255]);
 
@@ -105,6 +102,7 @@ fn basic() {
 }
 
 #[test]
+#[allow(clippy::excessive_precision)]
 fn red() {
     let data = &[
         Color::new(1.0, 0.0, 0.0, 1.0),
@@ -120,6 +118,12 @@ fn red() {
             0.12580287012451802,
             1.0,
         ),
+        Color::from_xyza_d65(0.4123908, 0.212639, 0.0193308, 1.0),
+        Color::from_xyza_d50(0.4360657, 0.2224932, 0.0139239, 1.0),
+        Color::from_display_p3_rgba(0.9174875, 0.2002869, 0.1385606, 1.0),
+        Color::from_a98_rgba(0.8585916, 0.0, 0.0, 1.0),
+        Color::from_prophoto_rgba(0.7022480, 0.2757205, 0.1035477, 1.0),
+        Color::from_rec2020_rgba(0.7919772, 0.2309758, 0.0737615, 1.0),
         Color::from_html("#f00").unwrap(),
         Color::from_html("hsv(360,100%,100%)").unwrap(),
     ];
@@ -183,16 +187,713 @@ fn interpolate() {
     assert_eq!(a.interpolate_hsv(&b, 0.5).to_rgba8(), [0, 255, 255, 255]);
     assert_eq!(a.interpolate_hsv(&b, 1.0).to_rgba8(), [0, 0, 255, 255]);
 
+    // powerless hue
+    let gray = Color::new(0.5, 0.5, 0.5, 1.0);
+    assert_eq!(gray.interpolate_hsv(&b, 0.5).to_rgba8(), [96, 96, 191, 255]);
+    assert_eq!(b.interpolate_hsv(&gray, 0.5).to_rgba8(), [96, 96, 191, 255]);
+
     assert_eq!(a.interpolate_oklab(&b, 0.0).to_rgba8(), [0, 255, 1, 255]);
     assert_eq!(a.interpolate_oklab(&b, 0.5).to_rgba8(), [0, 170, 191, 255]);
     assert_eq!(a.interpolate_oklab(&b, 1.0).to_rgba8(), [0, 0, 255, 255]);
 
-    #[cfg(feature = "lab")]
-    {
-        assert_eq!(a.interpolate_lab(&b, 0.0).to_rgba8(), [0, 255, 0, 255]);
-        assert_eq!(a.interpolate_lab(&b, 1.0).to_rgba8(), [0, 0, 255, 255]);
+    assert_eq!(a.interpolate_lab(&b, 0.0).to_rgba8(), [0, 255, 0, 255]);
+    assert_eq!(a.interpolate_lab(&b, 1.0).to_rgba8(), [0, 0, 255, 255]);
+
+    assert_eq!(a.interpolate_lch(&b, 0.0).to_rgba8(), [0, 255, 0, 255]);
+    assert_eq!(a.interpolate_lch(&b, 1.0).to_rgba8(), [0, 0, 255, 255]);
+}
+
+#[test]
+fn predefined_color_spaces() {
+    let data = [
+        Color::new(0.0, 0.0, 0.0, 1.0),
+        Color::new(1.0, 1.0, 1.0, 1.0),
+        Color::new(0.2, 0.6, 0.9, 0.5),
+        Color::from_rgba8(102, 51, 153, 255),
+        Color::from_rgba8(255, 215, 0, 255),
+    ];
+    for c in data {
+        let [r, g, b, a] = c.to_display_p3_rgba();
+        assert_eq!(
+            Color::from_display_p3_rgba(r, g, b, a).to_rgba8(),
+            c.to_rgba8()
+        );
+
+        let [r, g, b, a] = c.to_a98_rgba();
+        assert_eq!(Color::from_a98_rgba(r, g, b, a).to_rgba8(), c.to_rgba8());
+
+        let [r, g, b, a] = c.to_prophoto_rgba();
+        assert_eq!(
+            Color::from_prophoto_rgba(r, g, b, a).to_rgba8(),
+            c.to_rgba8()
+        );
+
+        let [r, g, b, a] = c.to_rec2020_rgba();
+        assert_eq!(
+            Color::from_rec2020_rgba(r, g, b, a).to_rgba8(),
+            c.to_rgba8()
+        );
+
+        let [x, y, z, a] = c.to_xyza_d65();
+        assert_eq!(Color::from_xyza_d65(x, y, z, a).to_rgba8(), c.to_rgba8());
+
+        let [x, y, z, a] = c.to_xyza_d50();
+        assert_eq!(Color::from_xyza_d50(x, y, z, a).to_rgba8(), c.to_rgba8());
+    }
+}
+
+#[test]
+fn chromatic_adaptation() {
+    use csscolorparser::{ChromaticAdaptation, WhitePoint};
+
+    fn assert_close(a: [f32; 3], b: [f32; 3]) {
+        for (x, y) in a.iter().zip(b.iter()) {
+            assert!((x - y).abs() < 1e-4, "{:?} != {:?}", a, b);
+        }
+    }
+
+    let methods = [
+        ChromaticAdaptation::Bradford,
+        ChromaticAdaptation::Cat02,
+        ChromaticAdaptation::Cat16,
+        ChromaticAdaptation::VonKries,
+    ];
+    let whites = [
+        WhitePoint::A,
+        WhitePoint::C,
+        WhitePoint::D50,
+        WhitePoint::D55,
+        WhitePoint::D65,
+        WhitePoint::D75,
+        WhitePoint::E,
+        WhitePoint::from_chromaticity(0.32, 0.33),
+        WhitePoint::new([95.0, 100.0, 110.0]),
+    ];
+    let c = Color::from_rgba8(102, 51, 153, 255);
+
+    for method in methods {
+        for white in whites {
+            // White maps to white
+            let xyz = method.adapt(WhitePoint::D65.xyz(), WhitePoint::D65, white);
+            assert_close(xyz, white.xyz());
+            let [x, y, z, a] = Color::new(1.0, 1.0, 1.0, 1.0).to_xyza(white, method);
+            assert_close([x, y, z], white.xyz());
+            assert_eq!(a, 1.0);
+
+            // Round trip
+            let [x, y, z, a] = c.to_xyza(white, method);
+            let back = method.adapt(
+                method.adapt([x, y, z], white, WhitePoint::E),
+                WhitePoint::E,
+                white,
+            );
+            assert_close(back, [x, y, z]);
+            assert_eq!(
+                Color::from_xyza([x, y, z], a, white, method).to_rgba8(),
+                c.to_rgba8()
+            );
+        }
+    }
+
+    // Bradford is the adaptation of CSS
+    let [x, y, z, _] = c.to_xyza(WhitePoint::D50, ChromaticAdaptation::Bradford);
+    let [x2, y2, z2, _] = c.to_xyza_d50();
+    assert_close([x, y, z], [x2, y2, z2]);
+
+    // sRGB red relative to D50
+    let [x, y, z, _] =
+        Color::new(1.0, 0.0, 0.0, 1.0).to_xyza(WhitePoint::D50, ChromaticAdaptation::Bradford);
+    assert_close([x, y, z], [0.4361, 0.2225, 0.0139]);
+}
+
+#[test]
+fn rgb_space() {
+    use csscolorparser::{RgbSpace, TransferFunction, WhitePoint};
+
+    fn assert_close(a: [f32; 4], b: [f32; 4]) {
+        for (x, y) in a.iter().zip(b.iter()) {
+            assert!((x - y).abs() < 1e-4, "{:?} != {:?}", a, b);
+        }
+    }
+
+    let srgb = RgbSpace::new([[0.64, 0.33], [0.3, 0.6], [0.15, 0.06]], WhitePoint::D65)
+        .transfer(TransferFunction::Srgb);
+    let display_p3 = RgbSpace::new([[0.68, 0.32], [0.265, 0.69], [0.15, 0.06]], WhitePoint::D65)
+        .transfer(TransferFunction::Parametric {
+            g: 2.4,
+            a: 1.0 / 1.055,
+            b: 0.055 / 1.055,
+            c: 1.0 / 12.92,
+            d: 0.04045,
+            e: 0.0,
+            f: 0.0,
+        });
+    // The inverse of the BT.2020 camera curve, like `color(rec2020)`
+    let (alpha, beta) = (1.099_296_8, 0.018_053_97);
+    let rec2020 = RgbSpace::new(
+        [[0.708, 0.292], [0.17, 0.797], [0.131, 0.046]],
+        WhitePoint::D65,
+    )
+    .transfer(TransferFunction::Parametric {
+        g: 1.0 / 0.45,
+        a: 1.0 / alpha,
+        b: (alpha - 1.0) / alpha,
+        c: 1.0 / 4.5,
+        d: 4.5 * beta,
+        e: 0.0,
+        f: 0.0,
+    });
+    let prophoto = RgbSpace::new(
+        [[0.7347, 0.2653], [0.1596, 0.8404], [0.0366, 0.0001]],
+        WhitePoint::D50,
+    )
+    .transfer(TransferFunction::Parametric {
+        g: 1.8,
+        a: 1.0,
+        b: 0.0,
+        c: 1.0 / 16.0,
+        d: 1.0 / 32.0,
+        e: 0.0,
+        f: 0.0,
+    });
+
+    let data = [
+        Color::new(0.0, 0.0, 0.0, 1.0),
+        Color::new(1.0, 1.0, 1.0, 1.0),
+        Color::new(0.2, 0.6, 0.9, 0.5),
+        Color::new(1.2, -0.1, 0.5, 1.0),
+        Color::from_rgba8(102, 51, 153, 255),
+    ];
+    for c in data {
+        assert_close(srgb.from_color(&c), c.to_array());
+        assert_close(display_p3.from_color(&c), c.to_display_p3_rgba());
+        assert_close(rec2020.from_color(&c), c.to_rec2020_rgba());
+        assert_close(prophoto.from_color(&c), c.to_prophoto_rgba());
+
+        for space in [srgb, display_p3, rec2020, prophoto] {
+            assert_close(
+                space.to_color(space.from_color(&c)).to_array(),
+                c.to_array(),
+            );
+        }
+    }
+
+    // The matrix of sRGB
+    let m = srgb.to_xyz_matrix();
+    assert!((m[0][0] - 0.4124).abs() < 1e-4);
+    assert!((m[1][1] - 0.7152).abs() < 1e-4);
+    assert!((m[2][2] - 0.9505).abs() < 1e-4);
+    let [x, y, z] = WhitePoint::D65.xyz();
+    let id = srgb.from_xyz_matrix();
+    assert!((id[0][0] * x + id[0][1] * y + id[0][2] * z - 1.0).abs() < 1e-4);
+
+    // BT.1886 with the black of a display lifts the black level
+    let t = TransferFunction::Bt1886 {
+        white: 100.0,
+        black: 0.1,
+    };
+    assert!((t.to_linear(0.0) - 0.001).abs() < 1e-6);
+    assert!((t.to_linear(1.0) - 1.0).abs() < 1e-6);
+    for x in [0.0, 0.1, 0.5, 0.9, 1.0] {
+        assert!((t.from_linear(t.to_linear(x)) - x).abs() < 1e-4);
+    }
+
+    // Gamma and negative values
+    let t = TransferFunction::Gamma(2.2);
+    assert!((t.to_linear(0.5) - 0.5f32.powf(2.2)).abs() < 1e-6);
+    assert_eq!(t.to_linear(-0.5), -t.to_linear(0.5));
+    assert_eq!(TransferFunction::Linear.from_linear(0.25), 0.25);
+}
+
+#[test]
+fn mix() {
+    let a = Color::new(1.0, 0.0, 0.0, 1.0);
+    let b = Color::new(0.0, 0.0, 1.0, 1.0);
+    let shorter = HueInterpolation::Shorter;
+
+    let c = a.mix(&b, ColorSpace::Srgb, shorter, None, None).unwrap();
+    assert_eq!(c.to_rgba8(), [128, 0, 128, 255]);
+
+    let c = a
+        .mix(&b, ColorSpace::Srgb, shorter, Some(0.3), None)
+        .unwrap();
+    assert_eq!(c.to_rgba8(), [77, 0, 179, 255]);
+
+    let c = a
+        .mix(&b, ColorSpace::Srgb, shorter, None, Some(0.7))
+        .unwrap();
+    assert_eq!(c.to_rgba8(), [77, 0, 179, 255]);
+
+    let c = a
+        .mix(&b, ColorSpace::Srgb, shorter, Some(0.2), Some(0.2))
+        .unwrap();
+    assert_eq!(c.to_rgba8(), [128, 0, 128, 102]);
+
+    // mix in any space at 0% and 100% gives back the original colors
+    let spaces = [
+        ColorSpace::Srgb,
+        ColorSpace::SrgbLinear,
+        ColorSpace::DisplayP3,
+        ColorSpace::A98Rgb,
+        ColorSpace::ProphotoRgb,
+        ColorSpace::Rec2020,
+        ColorSpace::Oklab,
+        ColorSpace::Oklch,
+        ColorSpace::XyzD50,
+        ColorSpace::XyzD65,
+        ColorSpace::Hsl,
+        ColorSpace::Hwb,
+    ];
+    for space in spaces {
+        let c = a.mix(&b, space, shorter, Some(1.0), None).unwrap();
+        assert_eq!(c.to_rgba8(), [255, 0, 0, 255]);
+        let c = a.mix(&b, space, shorter, Some(0.0), None).unwrap();
+        assert_eq!(c.to_rgba8(), [0, 0, 255, 255]);
+    }
+
+    // hue interpolation
+    let a = Color::new(1.0, 0.0, 0.0, 1.0);
+    let b = Color::new(0.0, 1.0, 0.0, 1.0);
+    let test_data = [
+        (HueInterpolation::Shorter, [255, 255, 0, 255]),
+        (HueInterpolation::Longer, [0, 0, 255, 255]),
+        (HueInterpolation::Increasing, [255, 255, 0, 255]),
+        (HueInterpolation::Decreasing, [0, 0, 255, 255]),
+    ];
+    for (hue, expected) in test_data {
+        let c = a.mix(&b, ColorSpace::Hsl, hue, None, None).unwrap();
+        assert_eq!(c.to_rgba8(), expected);
+    }
+
+    assert!(a
+        .mix(&b, ColorSpace::Srgb, shorter, Some(0.0), Some(0.0))
+        .is_none());
+    assert!(a
+        .mix(&b, ColorSpace::Srgb, shorter, Some(-0.1), None)
+        .is_none());
+    assert!(a
+        .mix(&b, ColorSpace::Srgb, shorter, Some(1.1), None)
+        .is_none());
+}
+
+#[test]
+fn extended_range() {
+    fn assert_close(a: [f32; 4], b: [f32; 4]) {
+        for (x, y) in a.iter().zip(b.iter()) {
+            assert!((x - y).abs() < 1e-3, "{:?} != {:?}", a, b);
+        }
+    }
+
+    let c = Color::new(1.2, -0.1, 0.5, 1.0);
+    assert!(!c.is_in_gamut());
+    assert!(c.clamp().is_in_gamut());
+    assert_eq!(c.to_rgba8(), [255, 0, 128, 255]);
+
+    assert_close(
+        Color::from_linear_rgba(1.2, -0.1, 0.5, 1.0).to_linear_rgba(),
+        [1.2, -0.1, 0.5, 1.0],
+    );
+    assert_close(
+        Color::from_oklaba(0.5, 0.4, 0.0, 1.0).to_oklaba(),
+        [0.5, 0.4, 0.0, 1.0],
+    );
+    assert_close(
+        Color::from_xyza_d65(0.1, 0.9, 0.1, 1.0).to_xyza_d65(),
+        [0.1, 0.9, 0.1, 1.0],
+    );
+    assert_close(
+        Color::from_display_p3_rgba(1.0, 0.0, 0.0, 1.0).to_display_p3_rgba(),
+        [1.0, 0.0, 0.0, 1.0],
+    );
+    assert_close(
+        Color::from_rec2020_rgba(0.0, 1.0, 0.0, 1.0).to_rec2020_rgba(),
+        [0.0, 1.0, 0.0, 1.0],
+    );
+    assert_close(
+        Color::from_hsla(0.0, 1.0, 0.5, 1.0).to_array(),
+        [1.0, 0.0, 0.0, 1.0],
+    );
+    assert_close(
+        Color::from_hsla(0.0, 1.5, 0.5, 1.0).to_array(),
+        [1.25, -0.25, -0.25, 1.0],
+    );
+    assert_close(
+        Color::from_hsva(0.0, 1.0, 1.2, 1.0).to_array(),
+        [1.2, 0.0, 0.0, 1.0],
+    );
+    assert_close(
+        Color::from_hwba(0.0, -0.2, 0.0, 1.0).to_array(),
+        [1.0, -0.2, -0.2, 1.0],
+    );
+
+    let data = [
+        Color::from_oklaba(0.5, 0.4, 0.0, 1.0),
+        Color::from_display_p3_rgba(1.0, 0.0, 0.0, 1.0),
+        Color::from_rec2020_rgba(0.0, 1.0, 0.0, 1.0),
+    ];
+    for c in data {
+        assert!(!c.is_in_gamut(), "{:?}", c);
+    }
+
+    let data = [
+        Color::new(1.0, 1.0, 1.0, 1.0),
+        Color::from_oklaba(1.0, 0.0, 0.0, 1.0),
+        Color::from_hsla(120.0, 1.0, 0.5, 1.0),
+        Color::from_display_p3_rgba(0.5, 0.5, 0.5, 1.0),
+    ];
+    for c in data {
+        assert!(c.is_in_gamut(), "{:?}", c);
+    }
+
+    assert_close(
+        Color::from_laba(50.0, 100.0, 0.0, 1.0).to_laba(),
+        [50.0, 100.0, 0.0, 1.0],
+    );
+}
+
+#[test]
+fn distance() {
+    use csscolorparser::DeltaE;
+
+    let methods = [
+        DeltaE::Cie76,
+        DeltaE::Cie94GraphicArts,
+        DeltaE::Cie94Textiles,
+        DeltaE::Ciede2000,
+        DeltaE::Cmc { l: 2.0, c: 1.0 },
+        DeltaE::Ok,
+        DeltaE::Itp,
+    ];
+    let data = [
+        Color::new(0.0, 0.0, 0.0, 1.0),
+        Color::new(1.0, 1.0, 1.0, 1.0),
+        Color::from_rgba8(102, 51, 153, 255),
+        Color::from_rgba8(255, 215, 0, 255),
+        Color::from_display_p3_rgba(0.0, 1.0, 0.0, 1.0),
+    ];
+    for method in methods {
+        for a in &data {
+            assert!(a.distance(a, method).abs() < 1e-3, "{:?}", method);
+            for b in &data {
+                let d = a.distance(b, method);
+                assert!(d.is_finite() && d >= 0.0, "{:?}", method);
+                if !matches!(
+                    method,
+                    DeltaE::Cie94GraphicArts | DeltaE::Cie94Textiles | DeltaE::Cmc { .. }
+                ) {
+                    assert!((d - b.distance(a, method)).abs() < 1e-3, "{:?}", method);
+                }
+            }
+        }
+        // Closer colors are closer
+        let a = Color::from_rgba8(200, 50, 50, 255);
+        let near = Color::from_rgba8(205, 50, 50, 255);
+        let far = Color::from_rgba8(50, 50, 200, 255);
+        assert!(
+            a.distance(&near, method) < a.distance(&far, method),
+            "{:?}",
+            method
+        );
+    }
+
+    // CIE Lab of the Sharma et al. CIEDE2000 test data, through `Color`
+    let test_data = [
+        ([50.0, 2.5, 0.0], [73.0, 25.0, -18.0], 27.1492),
+        ([50.0, 2.5, 0.0], [61.0, -5.0, 29.0], 22.8977),
+        (
+            [60.2574, -34.0099, 36.2677],
+            [60.4626, -34.1751, 39.4387],
+            1.2644,
+        ),
+        (
+            [22.7233, 20.0904, -46.6940],
+            [23.0331, 14.9730, -42.5619],
+            2.0373,
+        ),
+        (
+            [90.9257, -0.5406, -0.9208],
+            [88.6381, -0.8985, -0.7239],
+            1.5381,
+        ),
+    ];
+    for ([l1, a1, b1], [l2, a2, b2], expected) in test_data {
+        let c1 = Color::from_laba(l1, a1, b1, 1.0);
+        let c2 = Color::from_laba(l2, a2, b2, 1.0);
+        let d = c1.distance(&c2, DeltaE::Ciede2000);
+        assert!((d - expected).abs() < 1e-3, "{}: {}", expected, d);
+        let d = c1.distance(&c2, DeltaE::Cie76);
+        let e = ((l1 - l2).powi(2) + (a1 - a2).powi(2) + (b1 - b2).powi(2)).sqrt();
+        assert!((d - e).abs() < 1e-3);
+    }
+
+    // Black and white
+    let black = Color::new(0.0, 0.0, 0.0, 1.0);
+    let white = Color::new(1.0, 1.0, 1.0, 1.0);
+    assert!((black.distance(&white, DeltaE::Cie76) - 100.0).abs() < 1e-3);
+    assert!((black.distance(&white, DeltaE::Ciede2000) - 100.0).abs() < 1e-3);
+    assert!((black.distance(&white, DeltaE::Ok) - 1.0).abs() < 1e-3);
+    // The PQ of the white of SDR content at 203 cd/m²
+    assert!((black.distance(&white, DeltaE::Itp) - 418.095).abs() < 0.05);
+}
+
+#[test]
+fn contrast() {
+    use csscolorparser::{TextSize, WcagLevel};
+
+    let white = Color::new(1.0, 1.0, 1.0, 1.0);
+    let black = Color::new(0.0, 0.0, 0.0, 1.0);
+    let gray = |t: u8| Color::from_rgba8(t, t, t, 255);
+
+    assert_eq!(black.relative_luminance(), 0.0);
+    assert!((white.relative_luminance() - 1.0).abs() < 1e-6);
+    assert!((Color::from_rgba8(0, 255, 0, 255).relative_luminance() - 0.7152).abs() < 1e-6);
+
+    let test_data = [
+        (gray(0x76), 4.5422),
+        (gray(0x77), 4.4781),
+        (gray(0x59), 7.0047),
+        (gray(0x95), 2.9953),
+        (Color::from_rgba8(255, 69, 0, 255), 3.4408),
+        (black.clone(), 21.0),
+        (white.clone(), 1.0),
+    ];
+    for (c, expected) in test_data {
+        assert!(
+            (c.contrast_ratio(&white) - expected).abs() < 1e-3,
+            "{:?}",
+            c
+        );
+        assert!(
+            (white.contrast_ratio(&c) - expected).abs() < 1e-3,
+            "{:?}",
+            c
+        );
+    }
+
+    // WCAG levels
+    let levels = [
+        (gray(0x76), [true, true, false, true]),
+        (gray(0x77), [false, true, false, false]),
+        (gray(0x59), [true, true, true, true]),
+        (gray(0x95), [false, false, false, false]),
+    ];
+    for (c, expected) in levels {
+        let result = [
+            c.meets_wcag(&white, WcagLevel::Aa, TextSize::Normal),
+            c.meets_wcag(&white, WcagLevel::Aa, TextSize::Large),
+            c.meets_wcag(&white, WcagLevel::Aaa, TextSize::Normal),
+            c.meets_wcag(&white, WcagLevel::Aaa, TextSize::Large),
+        ];
+        assert_eq!(result, expected, "{:?}", c);
+    }
+    assert!(WcagLevel::Aaa > WcagLevel::Aa);
+
+    // APCA, from the reference implementation
+    let test_data = [
+        (gray(0x88), white.clone(), 63.056),
+        (white.clone(), gray(0x88), -68.541),
+        (black.clone(), gray(0xaa), 58.146),
+        (gray(0xaa), black.clone(), -56.241),
+        (black.clone(), white.clone(), 106.041),
+        (white.clone(), black.clone(), -107.885),
+        (gray(0x80), gray(0x80), 0.0),
+    ];
+    for (text, background, expected) in test_data {
+        let lc = text.apca_contrast(&background);
+        assert!(
+            (lc - expected).abs() < 1e-2,
+            "{:?} {:?}: {}",
+            text,
+            background,
+            lc
+        );
+    }
 
-        assert_eq!(a.interpolate_lch(&b, 0.0).to_rgba8(), [0, 255, 0, 255]);
-        assert_eq!(a.interpolate_lch(&b, 1.0).to_rgba8(), [0, 0, 255, 255]);
+    // Translucent text is composited over the background
+    let text = Color::new(0.0, 0.0, 0.0, 0.5);
+    let composited = text.composite(&white);
+    assert_eq!(composited.to_rgba8(), [128, 128, 128, 255]);
+    assert_eq!(
+        text.contrast_ratio(&white),
+        composited.contrast_ratio(&white)
+    );
+    assert_eq!(text.apca_contrast(&white), composited.apca_contrast(&white));
+    assert_eq!(
+        Color::new(1.0, 0.0, 0.0, 0.0).composite(&white).to_rgba8(),
+        [255, 255, 255, 255]
+    );
+    assert!((Color::new(0.0, 0.0, 0.0, 0.0).contrast_ratio(&white) - 1.0).abs() < 1e-6);
+
+    // A translucent background is composited over white
+    let background = Color::new(0.0, 0.0, 0.0, 0.5);
+    let opaque = Color::new(0.5, 0.5, 0.5, 1.0);
+    assert_eq!(background.composite(&white), opaque);
+    let composited = text.composite(&opaque);
+    assert_eq!(composited.to_array(), [0.25, 0.25, 0.25, 1.0]);
+    assert_eq!(
+        text.relative_luminance_over(&background),
+        composited.relative_luminance()
+    );
+    assert_eq!(
+        text.contrast_ratio(&background),
+        composited.contrast_ratio(&opaque)
+    );
+    assert_eq!(
+        text.apca_contrast(&background),
+        composited.apca_contrast(&opaque)
+    );
+    assert_eq!(
+        text.meets_wcag(&background, WcagLevel::Aa, TextSize::Large),
+        composited.meets_wcag(&opaque, WcagLevel::Aa, TextSize::Large)
+    );
+    let transparent = Color::new(0.0, 0.0, 0.0, 0.0);
+    assert_eq!(
+        text.contrast_ratio(&transparent),
+        text.contrast_ratio(&white)
+    );
+    assert!((black.contrast_ratio(&transparent) - 21.0).abs() < 1e-4);
+}
+
+#[test]
+fn gamut_mapping() {
+    use csscolorparser::{Gamut, GamutMapping};
+
+    let gamuts = [Gamut::Srgb, Gamut::DisplayP3, Gamut::Rec2020];
+    let methods = [GamutMapping::Css, GamutMapping::Clip, GamutMapping::Chroma];
+
+    let data = [
+        Color::from_oklcha(0.8, 0.35, 30f32.to_radians(), 1.0),
+        Color::from_oklcha(0.5, 0.4, 270f32.to_radians(), 0.5),
+        Color::from_oklcha(0.99, 0.3, 100f32.to_radians(), 1.0),
+        Color::from_display_p3_rgba(1.0, 0.0, 0.0, 1.0),
+        Color::from_rec2020_rgba(0.0, 1.0, 0.0, 1.0),
+        Color::new(1.2, -0.1, 0.0, 1.0),
+    ];
+    for c in &data {
+        let [l, _, h, alpha] = c.to_oklcha();
+        for gamut in gamuts {
+            for method in methods {
+                let mapped = c.to_gamut(gamut, method);
+                assert!(gamut.contains(&mapped), "{:?} {:?} {:?}", c, gamut, method);
+                assert_eq!(mapped.a, alpha);
+
+                if gamut.contains(c) {
+                    assert_eq!(&mapped, c);
+                }
+                // Chroma reduction keeps lightness and hue, the CSS
+                // algorithm stays within a just noticeable difference
+                let [l2, _, h2, _] = mapped.to_oklcha();
+                match method {
+                    GamutMapping::Css => assert!((l - l2).abs() < 0.02, "{:?}", c),
+                    GamutMapping::Chroma => {
+                        assert!((l - l2).abs() < 1e-3, "{:?}", c);
+                        assert!((h - h2).abs() < 0.01, "{:?}", c);
+                    }
+                    _ => {}
+                }
+            }
+        }
+        assert_eq!(
+            c.to_gamut(Gamut::Srgb, GamutMapping::Clip).to_rgba8(),
+            c.to_rgba8()
+        );
+    }
+
+    // In every gamut
+    let data = [
+        Color::new(0.2, 0.4, 0.6, 1.0),
+        Color::new(1.0, 1.0, 1.0, 1.0),
+        Color::new(0.0, 0.0, 0.0, 0.5),
+    ];
+    for c in &data {
+        for gamut in gamuts {
+            for method in methods {
+                assert_eq!(&c.to_gamut(gamut, method), c);
+            }
+        }
+    }
+
+    let c = Color::from_display_p3_rgba(0.0, 1.0, 0.0, 1.0);
+    assert!(!Gamut::Srgb.contains(&c));
+    assert!(Gamut::DisplayP3.contains(&c));
+    assert!(Gamut::Rec2020.contains(&c));
+
+    // The red primary of Display P3 is just outside Rec. 2020
+    let c = Color::from_display_p3_rgba(1.0, 0.0, 0.0, 1.0);
+    assert!(!Gamut::Rec2020.contains(&c));
+    assert_eq!(c.to_rgba8(), [255, 0, 0, 255]);
+    assert_eq!(c.to_rgba8_mapped(GamutMapping::Css), [255, 11, 12, 255]);
+    assert_eq!(c.to_rgba8_mapped(GamutMapping::Clip), [255, 0, 0, 255]);
+
+    // Lightness out of range
+    let c = Color::from_oklcha(1.2, 0.1, 0.0, 1.0);
+    assert_eq!(c.to_rgba8_mapped(GamutMapping::Css), [255, 255, 255, 255]);
+    let c = Color::from_oklcha(-0.1, 0.1, 0.0, 1.0);
+    assert_eq!(c.to_rgba8_mapped(GamutMapping::Chroma), [0, 0, 0, 255]);
+}
+
+#[test]
+fn gamut_geometry() {
+    use csscolorparser::Gamut;
+
+    // Brute force: the largest sampled chroma in gamut
+    fn sampled_max_chroma(gamut: Gamut, l: f32, h: f32) -> f32 {
+        (0..=1000)
+            .map(|i| i as f32 * 0.0005)
+            .filter(|&c| gamut.contains(&Color::from_oklcha(l, c, h, 1.0)))
+            .fold(0.0, f32::max)
+    }
+
+    for gamut in [Gamut::Srgb, Gamut::DisplayP3, Gamut::Rec2020] {
+        for h in (0..360).step_by(15) {
+            let h = (h as f32).to_radians();
+            for l in [0.05, 0.2, 0.4, 0.6, 0.8, 0.95] {
+                let c = gamut.max_chroma(l, h);
+                let sampled = sampled_max_chroma(gamut, l, h);
+                assert!(
+                    (c - sampled).abs() < 0.001,
+                    "{:?} l={} h={}: {} != {}",
+                    gamut,
+                    l,
+                    h,
+                    c,
+                    sampled
+                );
+            }
+
+            let [l, c] = gamut.cusp(h);
+            assert!(gamut.contains(&Color::from_oklcha(l, c - 0.001, h, 1.0)));
+            let [_, _, h2, _] = Color::from_oklcha(l, c, h, 1.0).to_oklcha();
+            assert!((h2 - h).sin().abs() < 1e-3, "{:?} h={}", gamut, h);
+
+            // No lightness has more chroma, the sampling misses the tip of
+            // the cusp
+            let (sampled_l, sampled_c) = (1..100)
+                .map(|i| i as f32 * 0.01)
+                .map(|l| (l, sampled_max_chroma(gamut, l, h)))
+                .fold((0.0, 0.0), |a, b| if b.1 > a.1 { b } else { a });
+            assert!(c >= sampled_c - 0.001, "{:?} h={}", gamut, h);
+            assert!(c - sampled_c < 0.01, "{:?} h={}", gamut, h);
+            assert!((l - sampled_l).abs() < 0.02, "{:?} h={}", gamut, h);
+        }
+
+        assert_eq!(gamut.max_chroma(0.0, 1.0), 0.0);
+        assert_eq!(gamut.max_chroma(1.0, 1.0), 0.0);
+    }
+
+    // The cusps of the primary and secondary colors
+    let data = [
+        Color::new(1.0, 0.0, 0.0, 1.0),
+        Color::new(1.0, 1.0, 0.0, 1.0),
+        Color::new(0.0, 1.0, 0.0, 1.0),
+        Color::new(0.0, 1.0, 1.0, 1.0),
+        Color::new(0.0, 0.0, 1.0, 1.0),
+        Color::new(1.0, 0.0, 1.0, 1.0),
+    ];
+    for color in data {
+        let [l, c, h, _] = color.to_oklcha();
+        let [l2, c2] = Gamut::Srgb.cusp(h);
+        assert!((l - l2).abs() < 1e-3, "{:?}", color);
+        assert!((c - c2).abs() < 1e-3, "{:?}", color);
     }
 }
@@ -0,0 +1,108 @@
+use csscolorparser::{parse, Color};
+
+#[test]
+fn random_colors() {
+    // The color string is randomly generated, the expected color is computed
+    // with the sample code of CSS Color 4 (D50 Lab with the Bradford adaptation
+    // from D65). Unlike tests/firefox.rs, these are not captured from a browser.
+    let test_data = [
+        ("lab(64.792% -38.722 -3.476 / 27%)", [42, 176, 162, 69]),
+        ("lab(62.712 23.729% 11.063%)", [206, 131, 129, 255]),
+        ("lch(56.278% 64.032 121.129)", [95, 149, 18, 255]),
+        ("lch(90.719% 35.071% 2.846rad)", [111, 254, 197, 255]),
+        ("lab(69.057% -15.571 1.335)", [138, 177, 165, 255]),
+        ("lab(46.530 59.427% -44.435% / 0.020)", [189, 23, 206, 5]),
+        ("lch(31.405% 75.986 308.696deg)", [102, 39, 170, 255]),
+        ("lch(88.064% 34.108% 185.363)", [53, 247, 228, 255]),
+        ("lab(58.416% -5.231 23.337 / 18%)", [143, 143, 99, 46]),
+        ("lab(85.676 -50.427% 33.922% / 62%)", [74, 243, 128, 158]),
+        ("lch(75.426% 89.880 148.040grad)", [68, 213, 41, 255]),
+        ("lch(56.430% 25.475% 179.375deg)", [18, 153, 133, 255]),
+        ("lab(68.143% 12.304 -25.596 / 0.332)", [171, 160, 213, 85]),
+        ("lab(33.738 43.653% -75.161% / 0.174)", [63, 43, 235, 44]),
+        ("lch(55.108% 84.713 0.939turn / 44%)", [234, 42, 189, 112]),
+        ("lch(38.983% 60.785% 334.146grad / 8%)", [96, 63, 223, 20]),
+        ("lab(62.318% 40.422 -34.715 / 0.052)", [197, 123, 213, 13]),
+        ("lab(25.104 -4.415% -21.708%)", [9, 63, 101, 255]),
+        ("lch(81.620% 46.203 68.630 / 83%)", [251, 190, 122, 212]),
+        (
+            "lch(90.309% 44.081% 106.632grad / 47%)",
+            [243, 229, 93, 120],
+        ),
+        ("lab(79.956% -38.655 58.435)", [148, 217, 79, 255]),
+        ("lab(45.519 59.476% -30.960% / 0.954)", [196, 3, 175, 243]),
+        ("lch(67.886% 29.030 278.950deg / 32%)", [151, 164, 217, 82]),
+        ("lch(9.597% 3.674% 23.122)", [34, 24, 24, 255]),
+        ("lab(72.193% 12.507 69.463 / 41%)", [221, 167, 32, 105]),
+        ("lab(33.167 24.144% 18.937%)", [126, 56, 42, 255]),
+        ("lch(60.944% 63.762 82.370)", [181, 140, 12, 255]),
+        ("lch(79.100% 47.328% 2.420rad)", [99, 220, 102, 255]),
+        ("lab(74.107% 38.481 -3.416 / 0.245)", [246, 154, 190, 62]),
+        ("lab(33.977 47.156% -35.160% / 0.324)", [139, 15, 152, 83]),
+        ("lch(61.430% 31.953 233.736deg / 0.190)", [74, 159, 193, 48]),
+        ("lch(44.505% 64.404% 329.903grad)", [91, 81, 253, 255]),
+        ("lab(48.147% 71.582 61.417)", [223, 18, 6, 255]),
+        ("lab(51.128 -1.839% -18.507%)", [98, 124, 161, 255]),
+        ("lch(54.062% 32.308 0.796turn / 99%)", [122, 125, 183, 252]),
+        (
+            "lch(83.171% 17.853% 162.868grad / 31%)",
+            [170, 219, 178, 79],
+        ),
+        ("lab(62.295% -8.094 -33.824)", [98, 157, 210, 255]),
+        ("lab(39.266 27.939% -70.251%)", [20, 77, 241, 255]),
+        ("lch(56.520% 79.649 0.221rad)", [253, 42, 111, 255]),
+        ("lch(13.861% 50.706% 301.273deg)", [21, 7, 131, 255]),
+        ("lab(60.109% -27.463 39.023 / 0.174)", [111, 157, 72, 44]),
+        ("lab(46.278 -37.477% 37.333% / 89%)", [5, 128, 10, 227]),
+        ("lch(18.583% 77.246 340.987grad)", [61, 2, 139, 255]),
+        ("lch(41.115% 18.901% 251.169 / 74%)", [48, 103, 141, 189]),
+        ("lab(38.073% 60.871 -11.905)", [168, 19, 111, 255]),
+        ("lab(48.346 -19.702% -9.629% / 62%)", [39, 127, 134, 158]),
+        ("lch(68.879% 63.534 99.777 / 0.153)", [173, 172, 39, 39]),
+        ("lch(48.959% 62.348% 0.682rad)", [227, 18, 17, 255]),
+        ("lab(13.688% 25.824 17.295 / 87%)", [69, 15, 9, 222]),
+        ("lab(30.391 47.783% -80.548%)", [40, 27, 237, 255]),
+        ("lch(73.642% 27.076 0.309turn)", [176, 186, 134, 255]),
+        ("lch(35.710% 22.373% 4.447rad)", [10, 90, 136, 255]),
+        ("lab(38.644% 59.834 -75.093)", [126, 41, 217, 255]),
+        ("lab(73.736 -24.484% -21.825% / 0.533)", [55, 198, 230, 136]),
+        ("lch(65.049% 20.516 0.802turn)", [156, 155, 193, 255]),
+        ("lch(45.900% 20.193% 1.420rad / 36%)", [128, 105, 58, 92]),
+        ("lab(55.338% 70.226 -10.022 / 0.352)", [232, 61, 153, 90]),
+        ("lab(82.331 18.513% 7.203% / 0.213)", [251, 189, 189, 54]),
+        ("lch(64.702% 72.814 69.339grad / 54%)", [228, 131, 31, 138]),
+        ("lch(35.644% 70.080% 5.401rad / 43%)", [122, 10, 219, 110]),
+    ];
+    for (s, expected) in test_data {
+        let rgba = parse(s).unwrap().to_rgba8();
+        assert_eq!(expected, rgba, "{}", s);
+    }
+}
+
+#[test]
+fn spec_examples() {
+    // https://www.w3.org/TR/css-color-4/#specifying-lab-lch
+    let test_data = [
+        ("lab(46.2775% -47.5621 48.5837)", "#008000"),
+        ("lch(46.2775% 67.9830 134.3912)", "#008000"),
+        ("lab(100% 0 0)", "#ffffff"),
+        ("lch(0% 0 0)", "#000000"),
+    ];
+    for (a, b) in test_data {
+        assert_eq!(
+            parse(a).unwrap().to_rgba8(),
+            parse(b).unwrap().to_rgba8(),
+            "{}",
+            a
+        );
+    }
+
+    let [l, a, b, _] = Color::from_rgba8(0, 128, 0, 255).to_laba();
+    assert!((l - 46.2775).abs() < 0.01);
+    assert!((a - -47.5621).abs() < 0.01);
+    assert!((b - 48.5837).abs() < 0.01);
+
+    // The D50 white point is neutral
+    let [_, a, b, _] = Color::new(1.0, 1.0, 1.0, 1.0).to_laba();
+    assert!(a.abs() < 1e-3 && b.abs() < 1e-3);
+}
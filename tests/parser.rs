@@ -1,4 +1,4 @@
-use csscolorparser::{parse, Color};
+use csscolorparser::{parse, parse_detailed, Color, Expected, ParseColorError, Parser};
 
 #[test]
 fn parser() {
@@ -25,20 +25,17 @@ fn parser() {
         assert_eq!(expected, c);
     }
 
-    #[cfg(feature = "lab")]
-    {
-        let test_data = [
-            ("lab(0% 0 0)", [0, 0, 0, 255]),
-            ("lab(100% 0 0)", [255, 255, 255, 255]),
-            ("lab(0% 0 0 / 0.5)", [0, 0, 0, 128]),
-            ("lch(0% 0 0)", [0, 0, 0, 255]),
-            ("lch(100% 0 0)", [255, 255, 255, 255]),
-            ("lch(0% 0 0 / 0.5)", [0, 0, 0, 128]),
-        ];
+    let test_data = [
+        ("lab(0% 0 0)", [0, 0, 0, 255]),
+        ("lab(100% 0 0)", [255, 255, 255, 255]),
+        ("lab(0% 0 0 / 0.5)", [0, 0, 0, 128]),
+        ("lch(0% 0 0)", [0, 0, 0, 255]),
+        ("lch(100% 0 0)", [255, 255, 255, 255]),
+        ("lch(0% 0 0 / 0.5)", [0, 0, 0, 128]),
+    ];
 
-        for (s, expected) in test_data {
-            assert_eq!(expected, parse(s).unwrap().to_rgba8());
-        }
+    for (s, expected) in test_data {
+        assert_eq!(expected, parse(s).unwrap().to_rgba8());
     }
 }
 
@@ -54,9 +51,9 @@ fn equal() {
         ("hwb(290 30% 0%)", "hwb(290 0.3 0)"),
         ("hsl(180,50%,27%)", "hsl(180,0.5,0.27)"),
         ("rgb(255, 165, 0)", "hsl(38.824 100% 50%)"),
+        ("rgb(255 0% 0)", "#f00"),
         ("#7654CD", "rgb(46.27% 32.94% 80.39%)"),
-        //#[cfg(feature = "lab")]
-        //("#7654CD", "lab(44.36% 36.05 -58.99)"),
+        ("#7654CD", "lab(44.36% 36.05 -58.99)"),
     ];
 
     for (a, b) in test_data {
@@ -138,9 +135,9 @@ fn red() {
         "ff0000ff",
         "rgb(255,0,0)",
         "rgb(255 0 0)",
-        "rgb(700, -99, 0)", // clamp to 0..255
+        "rgb(700, -99, 0)", // out of gamut, clamped by to_rgba8()
         "rgb(100% 0% 0%)",
-        "rgb(200% -10% -100%)", // clamp to 0%..100%
+        "rgb(200% -10% -100%)", // out of gamut, clamped by to_rgba8()
         "rgb(255 0 0 100%)",
         " RGB ( 255 , 0 , 0 ) ",
         "RGB( 255   0   0 )",
@@ -222,7 +219,628 @@ fn lime_alpha() {
     }
 }
 
-#[cfg(all(feature = "named-colors", feature = "lab"))]
+#[test]
+fn color_function() {
+    let data = [
+        "color(srgb 1 0 0)",
+        "color(srgb 100% 0% 0%)",
+        "color(srgb 1 0 0 / 1)",
+        "color(srgb-linear 1 0 0)",
+        "color(display-p3 0.91749 0.20029 0.13856)",
+        "color(display-p3 91.749% 20.029% 13.856%)",
+        "color(a98-rgb 0.85859 0 0)",
+        "color(prophoto-rgb 0.70225 0.27572 0.10355)",
+        "color(rec2020 0.79198 0.23098 0.07376)",
+        "color(xyz 0.41239 0.21264 0.01933)",
+        "color(xyz-d65 0.41239 0.21264 0.01933)",
+        "color(xyz-d50 0.43607 0.22249 0.01392)",
+        "COLOR( SRGB 1 0 0 )",
+    ];
+
+    for s in data {
+        let c = parse(s).unwrap().to_rgba8();
+        assert_eq!([255, 0, 0, 255], c);
+    }
+
+    let test_data = [
+        ("color(srgb 0 1 0 / 50%)", [0, 255, 0, 128]),
+        ("color(srgb 0 1 0 / 0.5)", [0, 255, 0, 128]),
+        (
+            "color(srgb-linear 0.2158 0.2158 0.2158)",
+            [128, 128, 128, 255],
+        ),
+        ("color(xyz 0.95046 1 1.08906)", [255, 255, 255, 255]),
+        ("color(xyz-d50 0.9643 1 0.8251)", [255, 255, 255, 255]),
+        ("color(display-p3 1 1 1 / 0)", [255, 255, 255, 0]),
+    ];
+
+    for (s, expected) in test_data {
+        assert_eq!(expected, parse(s).unwrap().to_rgba8());
+    }
+
+    let test_data = [
+        "color(srgb 1 0)",
+        "color(srgb 1 0 0 1 0)",
+        "color(srgb x 0 0)",
+        "color(cmyk 1 0 0)",
+        "color(1 0 0)",
+    ];
+
+    for s in test_data {
+        assert_eq!(parse(s).unwrap_err().to_string(), "invalid color() format");
+    }
+}
+
+#[cfg(feature = "named-colors")]
+#[test]
+fn relative_color() {
+    let test_data = [
+        ("rgb(from red r g b)", "red"),
+        ("rgb(from #3366cc b g r)", "#cc6633"),
+        ("rgb(from #3366cc r g b / 50%)", "#3366cc80"),
+        ("rgb(from rgba(10,20,30,0.5) r g b)", "rgb(10 20 30 / 0.5)"),
+        ("rgba(from rgb(10 20 30) r 255 b / alpha)", "rgb(10 255 30)"),
+        ("rgb(from red 0 g 100%)", "#00f"),
+        ("hsl(from #3366cc h s 30%)", "hsl(220 60% 30%)"),
+        ("hsl(from #3366cc 0 s l)", "hsl(0 60% 50%)"),
+        ("hsl(from hsl(from red h s l) h s l)", "red"),
+        (
+            "hsl(from hsl(120 50% 40%) h s calc(l + 20))",
+            "hsl(120 50% 60%)",
+        ),
+        (
+            "hsl(from hsl(120 50% 40%) h calc(s / 2) l)",
+            "hsl(120 25% 40%)",
+        ),
+        ("hsl(from red h 50 l)", "hsl(0 50% 50%)"),
+        ("hwb(from #3366cc h calc(w + 10) b)", "hwb(220 30% 20%)"),
+        ("hsv(from #3366cc h s calc(v - 30))", "hsv(220 75% 50%)"),
+        ("hwb(from red h w b)", "red"),
+        ("hwb(from gray 120 0% b)", "hwb(120 0% 49.8%)"),
+        ("hsv(from lime h s v / 0.5)", "#00ff0080"),
+        ("oklab(from red l a b)", "red"),
+        ("oklab(from red l 0 0)", "oklab(0.62796 0 0)"),
+        ("oklch(from red l c h)", "red"),
+        (
+            "oklch(from lime l c 29.23388)",
+            "oklch(0.86644 0.29483 29.23388)",
+        ),
+        ("color(from red srgb r g b)", "red"),
+        ("color(from red srgb-linear r g b)", "red"),
+        ("color(from red display-p3 r g b)", "red"),
+        ("color(from red xyz-d50 x y z)", "red"),
+        ("color(from #3366cc srgb b g r)", "#cc6633"),
+    ];
+
+    for (a, b) in test_data {
+        assert_eq!(parse(a).unwrap().to_rgba8(), parse(b).unwrap().to_rgba8());
+    }
+
+    let test_data = [
+        ("lab(from #7654cd l a b)", "#7654cd"),
+        ("lch(from #7654cd l c h)", "#7654cd"),
+        ("lch(from #7654cd l 0 h)", "lab(from #7654cd l 0 0)"),
+    ];
+
+    for (a, b) in test_data {
+        assert_eq!(parse(a).unwrap().to_rgba8(), parse(b).unwrap().to_rgba8());
+    }
+
+    let test_data = [
+        "rgb(from)",
+        "rgb(from red)",
+        "rgb(from red r g)",
+        "rgb(from red r g x)",
+        "rgb(from red h s l)",
+        "rgb(from bloodred r g b)",
+        "hsl(from red r g b)",
+        "color(from red srgb x y z)",
+        "color(from red cmyk r g b)",
+    ];
+
+    for s in test_data {
+        assert!(parse(s).is_err());
+    }
+
+    // Origin colors are nested at most 32 levels deep
+    let nested = |n| "rgb(from ".repeat(n) + "red" + &" r g b)".repeat(n);
+    assert_eq!(parse(&nested(32)).unwrap().to_rgba8(), [255, 0, 0, 255]);
+    assert_eq!(parse(&nested(33)), Err(ParseColorError::InvalidRgb));
+    assert_eq!(parse(&nested(10_000)), Err(ParseColorError::InvalidRgb));
+
+    let err = parse_detailed(&nested(33)).unwrap_err();
+    assert_eq!(err.expected(), Expected::Nesting(32));
+}
+
+#[cfg(feature = "named-colors")]
+#[test]
+fn color_mix() {
+    let test_data = [
+        ("color-mix(in srgb, red, blue)", [128, 0, 128, 255]),
+        ("color-mix(in srgb, red 50%, blue 50%)", [128, 0, 128, 255]),
+        ("color-mix(in srgb, red 30%, blue)", [77, 0, 179, 255]),
+        ("color-mix(in srgb, 30% red, blue)", [77, 0, 179, 255]),
+        ("color-mix(in srgb, red, blue 70%)", [77, 0, 179, 255]),
+        ("color-mix(in srgb, red 60%, blue 60%)", [128, 0, 128, 255]),
+        ("color-mix(in srgb, red 20%, blue 20%)", [128, 0, 128, 102]),
+        ("color-mix(in srgb, transparent, blue)", [0, 0, 255, 128]),
+        (
+            "color-mix(in srgb, rgb(255 0 0 / 0.2), blue)",
+            [42, 0, 213, 153],
+        ),
+        ("color-mix(in srgb-linear, red, blue)", [188, 0, 188, 255]),
+        ("color-mix(in xyz, red, blue)", [188, 0, 188, 255]),
+        ("color-mix(in xyz-d50, red, blue)", [188, 0, 188, 255]),
+        ("color-mix(in oklab, red 0%, blue)", [0, 0, 255, 255]),
+        ("color-mix(in oklab, red 100%, blue)", [255, 0, 0, 255]),
+        ("color-mix(in hsl, red, lime)", [255, 255, 0, 255]),
+        (
+            "color-mix(in hsl shorter hue, red, lime)",
+            [255, 255, 0, 255],
+        ),
+        ("color-mix(in hsl longer hue, red, lime)", [0, 0, 255, 255]),
+        (
+            "color-mix(in hsl increasing hue, lime, red)",
+            [0, 0, 255, 255],
+        ),
+        (
+            "color-mix(in hsl decreasing hue, red, lime)",
+            [0, 0, 255, 255],
+        ),
+        ("color-mix(in hwb, red, lime)", [255, 255, 0, 255]),
+        ("COLOR-MIX(IN SRGB, RED, BLUE)", [128, 0, 128, 255]),
+        (
+            "color-mix(in srgb, color-mix(in srgb, red, blue), rgb(from blue r g b))",
+            [64, 0, 191, 255],
+        ),
+    ];
+
+    for (s, expected) in test_data {
+        assert_eq!(expected, parse(s).unwrap().to_rgba8());
+    }
+
+    let test_data = [
+        "color-mix()",
+        "color-mix(in srgb, red)",
+        "color-mix(in srgb, red, blue, lime)",
+        "color-mix(srgb, red, blue)",
+        "color-mix(in cmyk, red, blue)",
+        "color-mix(in srgb longer hue, red, blue)",
+        "color-mix(in hsl longest hue, red, blue)",
+        "color-mix(in hsl longer, red, blue)",
+        "color-mix(in srgb, red 0%, blue 0%)",
+        "color-mix(in srgb, red -10%, blue)",
+        "color-mix(in srgb, red 150%, blue)",
+        "color-mix(in srgb, red 60%, blue 140%)",
+        "color-mix(in srgb, red 50% 50%, blue)",
+        "color-mix(in srgb, red 0.5, blue)",
+        "color-mix(in srgb, red, bloodred)",
+    ];
+
+    for s in test_data {
+        assert!(parse(s).is_err());
+    }
+
+    #[rustfmt::skip]
+    let test_data = [
+        ("color-mix(in srgb, red)",           "invalid color-mix() format"),
+        ("color-mix(in cmyk, red, blue)",     "invalid color-mix() format"),
+        ("color-mix(in srgb, red, bloodred)", "invalid unknown format"),
+    ];
+
+    for (s, err_msg) in test_data {
+        assert_eq!(parse(s).unwrap_err().to_string(), err_msg);
+    }
+
+    // Mixed colors are nested at most 32 levels deep, with relative colors
+    let nested = |n| "color-mix(in srgb, red, ".repeat(n) + "red" + &")".repeat(n);
+    assert_eq!(parse(&nested(32)).unwrap().to_rgba8(), [255, 0, 0, 255]);
+    assert_eq!(parse(&nested(33)), Err(ParseColorError::InvalidColorMix));
+    assert_eq!(
+        parse(&nested(10_000)),
+        Err(ParseColorError::InvalidColorMix)
+    );
+
+    let s = "rgb(from ".repeat(16) + &nested(17) + &" r g b)".repeat(16);
+    assert_eq!(parse(&s), Err(ParseColorError::InvalidColorMix));
+}
+
+#[cfg(feature = "named-colors")]
+#[test]
+fn none_keyword() {
+    let test_data = [
+        ("rgb(none 0 0)", "#000"),
+        ("rgb(255 none none / none)", "#f000"),
+        ("rgb(none 100% 0%)", "lime"),
+        ("rgba(none, 255, none, 0.5)", "#00ff0080"),
+        ("hsl(none 0% 50%)", "hsl(0 0% 50%)"),
+        ("hsl(120 none 50%)", "hsl(0 0% 50%)"),
+        ("hsl(none 100% 50%)", "red"),
+        ("hwb(none 0% 0%)", "red"),
+        ("hsv(120 100% 100% / none)", "#00ff0000"),
+        ("oklab(none 0 0)", "black"),
+        ("oklch(0.7 0 none)", "oklch(0.7 0 0)"),
+        ("oklch(0.7 none 120)", "oklch(0.7 0 0)"),
+        ("color(srgb none 1 none)", "lime"),
+        ("color(display-p3 none none none)", "black"),
+        ("hsl(from red none s l)", "red"),
+        ("rgb(from red r none none)", "red"),
+    ];
+
+    for (a, b) in test_data {
+        assert_eq!(parse(a).unwrap().to_rgba8(), parse(b).unwrap().to_rgba8());
+    }
+
+    let test_data = [
+        ("lab(50% none none)", "lab(50% 0 0)"),
+        ("lch(50% 30 none)", "lch(50% 30 0)"),
+    ];
+
+    for (a, b) in test_data {
+        assert_eq!(parse(a).unwrap().to_rgba8(), parse(b).unwrap().to_rgba8());
+    }
+
+    // missing components are carried forward when mixing
+    let test_data = [
+        (
+            "color-mix(in hsl, hsl(none 100% 50%), hsl(120 100% 50%))",
+            "lime",
+        ),
+        ("color-mix(in srgb, rgb(none 0 0), rgb(255 0 0))", "red"),
+        (
+            "color-mix(in srgb, rgb(none 0 0), rgb(none 0 255))",
+            "#000080",
+        ),
+        (
+            "color-mix(in srgb, rgb(255 0 0 / none), rgb(0 0 255 / 0.5))",
+            "rgb(128 0 128 / 0.5)",
+        ),
+        (
+            "color-mix(in oklch, oklch(0.5 0.1 none), oklch(0.7 0.1 120))",
+            "oklch(0.6 0.1 120)",
+        ),
+        (
+            "color-mix(in oklch, hsl(none 50% 50%), oklch(0.6 0.1 120))",
+            "color-mix(in oklch, oklch(from hsl(0 50% 50%) l c none), oklch(0.6 0.1 120))",
+        ),
+        (
+            "color-mix(in srgb, color-mix(in srgb, rgb(none 0 0), rgb(none 0 0)), red)",
+            "red",
+        ),
+        // powerless hue of achromatic colors
+        ("color-mix(in hsl, white, blue)", "hsl(240 50% 75%)"),
+        ("color-mix(in hsl, blue, #808080)", "hsl(240 50% 50.1%)"),
+        ("color-mix(in hwb, white, red)", "hwb(0 50% 0%)"),
+        (
+            "color-mix(in oklch, #777, oklch(0.7 0.1 120))",
+            "color-mix(in oklch, oklch(from #777 l c none), oklch(0.7 0.1 120))",
+        ),
+    ];
+
+    for (a, b) in test_data {
+        assert_eq!(parse(a).unwrap().to_rgba8(), parse(b).unwrap().to_rgba8());
+    }
+
+    let test_data = [
+        "rgb(non 0 0)",
+        "rgb(none)",
+        "hsl(nonedeg 0% 0%)",
+        "hsl(0 none% 0%)",
+    ];
+
+    for s in test_data {
+        assert!(parse(s).is_err());
+    }
+}
+
+#[cfg(feature = "named-colors")]
+#[test]
+fn math_functions() {
+    let test_data = [
+        ("rgb(calc(255 * 0.5) 0 0)", "rgb(127.5 0 0)"),
+        ("rgb(calc(50% + 10%) 0% 0%)", "rgb(60% 0% 0%)"),
+        (
+            "rgb(min(300, 255) max(0, -10) clamp(0, 128, 255))",
+            "#ff0080",
+        ),
+        ("rgb(0 0 0 / calc(1 / 2))", "rgb(0 0 0 / 0.5)"),
+        ("rgb(calc(infinity) 0 0)", "red"),
+        ("rgb(calc(nan) 0 0)", "black"),
+        ("rgba(round(127.6), 0, abs(-255), 1)", "rgb(128 0 255)"),
+        ("hsl(calc(120deg + 30deg) 50% 50%)", "hsl(150deg 50% 50%)"),
+        ("hsl(calc(0.25turn * 2) 100% 50%)", "hsl(180 100% 50%)"),
+        ("hsl(atan2(1, 1) 100% 50%)", "hsl(45 100% 50%)"),
+        ("hsl(mod(-90deg, 360deg) 100% 50%)", "hsl(270 100% 50%)"),
+        ("hwb(120 calc(10% * 2) 0%)", "hwb(120 20% 0%)"),
+        (
+            "oklch(0.7 0.1 calc(infinity))",
+            "oklch(0.7 0.1 calc(infinity))",
+        ),
+        ("oklab(calc(0.5 + 0.1) 0 0)", "oklab(0.6 0 0)"),
+        ("color(srgb calc(0.5 * 2) 0 0)", "red"),
+        ("rgb(from red calc(r / 2) g b)", "rgb(127.5 0 0)"),
+        (
+            "rgb(from red r g b / calc(alpha / 2))",
+            "rgb(255 0 0 / 0.5)",
+        ),
+        ("hsl(from red calc(h + 120) s l)", "lime"),
+        (
+            "oklch(from red l c calc(h + 180))",
+            "oklch(from red l c calc(h - 180))",
+        ),
+        (
+            "color-mix(in srgb, red calc(25% * 2), lime)",
+            "color-mix(in srgb, red, lime)",
+        ),
+    ];
+
+    for (a, b) in test_data {
+        assert_eq!(
+            parse(a).unwrap().to_rgba8(),
+            parse(b).unwrap().to_rgba8(),
+            "{}",
+            a
+        );
+    }
+
+    let test_data = [
+        "rgb(calc(255), 0%, 0%)",
+        "rgb(calc() 0 0)",
+        "rgb(calc(1+2) 0 0)",
+        "rgb(calc(10deg) 0 0)",
+        "rgb(calc(10% + 10) 0 0)",
+        "hsl(calc(10%) 50% 50%)",
+        "hsl(calc(h + 10) 50% 50%)",
+        "rgb(foo(1) 0 0)",
+    ];
+
+    for s in test_data {
+        assert!(parse(s).is_err(), "{}", s);
+    }
+
+    let s = format!(
+        "rgb({}0{} 0 0)",
+        "calc(".repeat(100_000),
+        ")".repeat(100_000)
+    );
+    assert_eq!(parse(&s), Err(ParseColorError::InvalidRgb));
+}
+
+#[cfg(feature = "named-colors")]
+#[test]
+fn css_syntax() {
+    let test_data = [
+        ("rgb(0/**/0 0)", "black"),
+        ("/* red */ rgb(255 0 0) /* end */", "red"),
+        ("rgb(/**/255, 0/**/, 0)", "red"),
+        ("rgb(2.55e2 0 0)", "red"),
+        ("rgb(+255 -0 .0)", "red"),
+        ("rgb(25500E-2 0 0)", "red"),
+        ("hsl(0.5TURN 100% 50%)", "cyan"),
+        ("r\\67 b(255 0 0)", "red"),
+        ("\\72 ed", "red"),
+        ("R\\45 D", "red"),
+        ("#\\66 00", "red"),
+        ("rgb(from red \\72  g b)", "red"),
+        ("color(\\73 rgb 1 0 0)", "red"),
+        ("rgb(0 0 0 / n\\6f ne)", "rgb(0 0 0 / 0)"),
+        ("\n\trgb(\n255\n0\n0\n)\n", "red"),
+    ];
+
+    for (a, b) in test_data {
+        assert_eq!(
+            parse(a).unwrap().to_rgba8(),
+            parse(b).unwrap().to_rgba8(),
+            "{}",
+            a
+        );
+    }
+
+    let test_data = [
+        "rgb(inf nan 0)",
+        "rgb(infinity 0 0)",
+        "rgb(NaN 0 0)",
+        "rgb(1e 0 0)",
+        "rgb(1. 0 0)",
+        "rgb(0x10 0 0)",
+        "rgb(0,0 0)",
+        "rgb(0 0,0)",
+        "rgb(0,0,0 / 1)",
+        "rgb(0 0 / 0 1)",
+        "rgb(0 0 0 //1)",
+        "rgb(,0,0,0)",
+        "rgb(0,0,0,)",
+        "rgb(0,,0,0)",
+        "rgb('0' 0 0)",
+        "rgb(0 0 0) x",
+        "rgb(0 0 0))",
+        "hsl(0 100% 50% /* unterminated",
+        "red /* x */ blue",
+        "#f00 /**/ #0f0",
+        "rgb(0/*/0 0)",
+        "+fffff",
+        "#-fffff",
+    ];
+
+    for s in test_data {
+        assert!(parse(s).is_err(), "{}", s);
+    }
+}
+
+#[cfg(feature = "named-colors")]
+#[test]
+fn strict_mode() {
+    let strict = Parser::strict();
+
+    let test_data = [
+        ("#f00", "red"),
+        ("rgb(255,0,0)", "red"),
+        ("rgba(100%, 0%, 0%, 50%)", "#ff000080"),
+        ("rgb(255 0% 0 / 0.5)", "#ff000080"),
+        ("rgb(255 none 0)", "red"),
+        ("hsl(0, 100%, 50%, 0.5)", "#ff000080"),
+        ("hsl(0deg 100 50)", "red"),
+        ("hsl(0 100% 50 / 1)", "red"),
+        ("hwb(0 0 0)", "red"),
+        ("hwb(120 0% 50)", "green"),
+        ("hsl(from red h s calc(l / 2))", "hsl(0 100% 25%)"),
+        ("hwb(from red h calc(w + 100) b)", "white"),
+        ("oklch(from red l c h / 0.5)", "#ff000080"),
+        ("color(srgb 1 0 0 / 50%)", "#ff000080"),
+        ("color-mix(in srgb, red, blue)", "rgb(127.5 0 127.5)"),
+    ];
+    for (a, b) in test_data {
+        let c = strict.parse(a).unwrap().to_rgba8();
+        assert_eq!(c, parse(b).unwrap().to_rgba8(), "{}", a);
+    }
+
+    // Valid in lenient mode only
+    let test_data = [
+        "ff0000",
+        "hsv(0 100% 100%)",
+        "hsva(0,100%,100%,1)",
+        "hwba(0 0% 0%)",
+        "rgb (255 0 0)",
+        "rgb(255 0 0 1)",
+        "hwb(0, 0%, 0%)",
+        "oklab(0.6, 0.2, 0.1)",
+        "rgb(255, none, 0)",
+        "hsl(none, 100%, 50%)",
+        "rgba(255, 0, 0, none)",
+        "hsl(0, 1, 0.5)",
+        "rgb(from, red, r, g, b)",
+        "color-mix(in srgb, ff0000, blue)",
+        "color-mix(in srgb, hsv(0 100% 100%), blue)",
+    ];
+    for s in test_data {
+        assert!(parse(s).is_ok(), "{}", s);
+        assert!(strict.parse(s).is_err(), "{}", s);
+    }
+
+    // Invalid in both modes
+    let test_data = [
+        "rgb(255, 0%, 0)",
+        "hsl(0, 100%, 50)",
+        "rgb(0,0 0)",
+        "rgb(0 0 / 0 1)",
+    ];
+    for s in test_data {
+        assert!(parse(s).is_err(), "{}", s);
+        assert!(strict.parse(s).is_err(), "{}", s);
+    }
+
+    use Expected::*;
+    use ParseColorError::*;
+
+    #[rustfmt::skip]
+    let test_data = [
+        ("ff0000", InvalidUnknown, 0..6, "ff0000", Color),
+        ("hsv(0 100% 100%)", InvalidFunction, 0..3, "hsv", ColorFunction),
+        ("rgb (255 0 0)", InvalidUnknown, 0..3, "rgb", Color),
+        ("rgb(255 0 0 1)", InvalidRgb, 12..13, "1", Slash),
+        ("hwb(0, 0%, 0%)", InvalidHwb, 7..9, "0%", Whitespace),
+        ("rgb(255, none, 0)", InvalidRgb, 9..13, "none", NumberOrPercentage),
+        ("hsl(none, 100%, 50%)", InvalidHsl, 4..8, "none", NumberOrAngle),
+        ("hsl(0, 1, 0.5)", InvalidHsl, 7..8, "1", Percentage),
+    ];
+    for (s, kind, span, token, expected) in test_data {
+        let err = strict.parse_detailed(s).unwrap_err();
+        assert_eq!(err.kind(), kind, "{}", s);
+        assert_eq!(err.span(), span, "{}", s);
+        assert_eq!(err.token(), token, "{}", s);
+        assert_eq!(err.expected(), expected, "{}", s);
+    }
+}
+
+#[test]
+fn parser_options() {
+    assert_eq!(Parser::default(), Parser::new());
+
+    let p = Parser::strict().hex_without_hash(true);
+    assert_eq!(p.parse("00ff00").unwrap().to_rgba8(), [0, 255, 0, 255]);
+    assert!(Parser::new()
+        .hex_without_hash(false)
+        .parse("00ff00")
+        .is_err());
+
+    let p = Parser::strict().hsv(true);
+    assert_eq!(
+        p.parse("hsv(120 100% 100%)").unwrap().to_rgba8(),
+        [0, 255, 0, 255]
+    );
+    assert_eq!(
+        p.parse("hsva(120, 100%, 100%, 1)").unwrap().to_rgba8(),
+        [0, 255, 0, 255]
+    );
+    assert!(Parser::new()
+        .hsv(false)
+        .parse("hsv(120 100% 100%)")
+        .is_err());
+
+    let p = Parser::strict().hwba(true);
+    assert_eq!(
+        p.parse("hwba(120 0% 0%)").unwrap().to_rgba8(),
+        [0, 255, 0, 255]
+    );
+    assert!(Parser::new().hwba(false).parse("hwba(120 0% 0%)").is_err());
+    assert!(Parser::new().hwba(false).parse("hwb(120 0% 0%)").is_ok());
+
+    let p = Parser::strict().mixed_syntax(true);
+    assert_eq!(
+        p.parse("hwb(120, 0%, 0%)").unwrap().to_rgba8(),
+        [0, 255, 0, 255]
+    );
+    assert_eq!(
+        p.parse("rgb(0, 255, 0, none)").unwrap().to_rgba8(),
+        [0, 255, 0, 0]
+    );
+    assert_eq!(
+        p.parse("rgb (0 255 0 1)").unwrap().to_rgba8(),
+        [0, 255, 0, 255]
+    );
+    assert_eq!(
+        p.parse("hsl(120, 100, 50)").unwrap().to_rgba8(),
+        [0, 255, 0, 255]
+    );
+    assert!(Parser::new()
+        .mixed_syntax(false)
+        .parse("rgb(0 255 0 1)")
+        .is_err());
+
+    let p = Parser::new().number_fractions(false);
+    assert_eq!(
+        p.parse("hsl(120 100 50)").unwrap().to_rgba8(),
+        [0, 255, 0, 255]
+    );
+    assert_eq!(
+        p.parse("hwb(120 0 0)").unwrap().to_rgba8(),
+        [0, 255, 0, 255]
+    );
+    assert_eq!(
+        p.parse("hsv(120 100 100)").unwrap().to_rgba8(),
+        [0, 255, 0, 255]
+    );
+    assert_eq!(
+        p.parse("hsl(from #0f0 h s l)").unwrap().to_rgba8(),
+        [0, 255, 0, 255]
+    );
+    assert_eq!(
+        parse("hsl(120 1 0.5)").unwrap().to_rgba8(),
+        [0, 255, 0, 255]
+    );
+    assert_eq!(
+        parse("hsl(from #0f0 h s l)").unwrap().to_rgba8(),
+        [0, 255, 0, 255]
+    );
+
+    // Relative colors use the range of CSS either way
+    let s = "hsl(from hsl(120 50% 40%) h s calc(l + 20))";
+    for p in [Parser::new(), Parser::new().number_fractions(false)] {
+        assert_eq!(p.parse(s).unwrap().to_rgba8(), [102, 204, 102, 255]);
+    }
+}
+
+#[cfg(feature = "named-colors")]
 #[test]
 fn invalid_format() {
     let test_data = [
@@ -290,3 +908,841 @@ fn invalid_format() {
         assert_eq!(c.unwrap_err().to_string(), err_msg);
     }
 }
+
+#[cfg(feature = "named-colors")]
+#[test]
+fn detailed_errors() {
+    use Expected::*;
+    use ParseColorError::*;
+
+    #[rustfmt::skip]
+    let test_data = [
+        ("", InvalidUnknown, 0..0, "", Color),
+        ("bloodred", InvalidUnknown, 0..8, "bloodred", Color),
+        ("#78afzd", InvalidHex, 0..7, "#78afzd", HexDigits),
+        ("#fff #000", InvalidHex, 5..9, "#000", EndOfInput),
+        ("cmyk(0,0,0,0)", InvalidFunction, 0..4, "cmyk", ColorFunction),
+        ("rgb(255,0,0", InvalidUnknown, 11..11, "", CloseParen),
+        ("rgb(0 0 0) x", InvalidUnknown, 11..12, "x", EndOfInput),
+        ("rgb(0 0 0 0 0)", InvalidRgb, 4..13, "0 0 0 0 0", Arguments { count: "3 or 4", found: 5 }),
+        ("rgb(255, 0, 8s)", InvalidRgb, 12..14, "8s", NumberOrPercentage),
+        ("rgb(255,0,0%)", InvalidRgb, 10..12, "0%", Number),
+        ("rgb(70%,30%,0)", InvalidRgb, 12..13, "0", Percentage),
+        ("rgb(0,0 0)", InvalidRgb, 8..9, "0", Comma),
+        ("rgb(0 0,0)", InvalidRgb, 8..9, "0", Whitespace),
+        ("rgb(0 0 / 0 1)", InvalidRgb, 10..11, "0", Whitespace),
+        ("rgb(,0,0,0)", InvalidRgb, 4..5, ",", Argument),
+        ("rgb(0,0,0,)", InvalidRgb, 9..10, ",", Argument),
+        ("hsl(1%, 50%, 50%)", InvalidHsl, 4..6, "1%", NumberOrAngle),
+        ("hwb(270,0%,0%,x)", InvalidHwb, 14..15, "x", NumberOrPercentage),
+        ("color(cmyk 1 0 0)", InvalidColor, 6..10, "cmyk", ColorSpace),
+        ("color(srgb 1 0)", InvalidColor, 6..14, "srgb 1 0", Arguments { count: "4 or 5", found: 3 }),
+        ("rgb(from bloodred r g b)", InvalidUnknown, 9..17, "bloodred", Color),
+        ("color-mix(in srgb, red)", InvalidColorMix, 10..22, "in srgb, red", Arguments { count: "3 comma separated", found: 2 }),
+        ("color-mix(on srgb, red, blue)", InvalidColorMix, 10..12, "on", Keyword("in")),
+        ("color-mix(in cmyk, red, blue)", InvalidColorMix, 13..17, "cmyk", ColorSpace),
+        ("color-mix(in hsl sideways hue, red, blue)", InvalidColorMix, 17..25, "sideways", HueInterpolation),
+        ("color-mix(in srgb longer hue, red, blue)", InvalidColorMix, 18..24, "longer", Comma),
+        ("color-mix(in srgb, red 120%, blue)", InvalidColorMix, 23..27, "120%", MixPercentage),
+        ("color-mix(in srgb, red 0%, blue 0%)", InvalidColorMix, 10..34, "in srgb, red 0%, blue 0%", NonZeroPercentages),
+    ];
+
+    for (s, kind, span, token, expected) in test_data {
+        let err = parse_detailed(s).unwrap_err();
+        assert_eq!(err.kind(), kind, "{}", s);
+        assert_eq!(err.span(), span, "{}", s);
+        assert_eq!(err.token(), token, "{}", s);
+        assert_eq!(err.expected(), expected, "{}", s);
+        assert_eq!(parse(s).unwrap_err(), kind, "{}", s);
+        assert_eq!(ParseColorError::from(err), kind, "{}", s);
+    }
+
+    #[rustfmt::skip]
+    let test_data = [
+        ("rgb(0 0 0 0 0)", "invalid rgb format: expected 3 or 4 arguments, found 5 at 4..13"),
+        ("rgb(255, 0, 8s)", "invalid rgb format: expected a number or percentage, found `8s` at 12..14"),
+        ("rgb(255,0,0", "invalid unknown format: expected ')', found end of input at 11..11"),
+    ];
+
+    for (s, msg) in test_data {
+        assert_eq!(parse_detailed(s).unwrap_err().to_string(), msg);
+    }
+}
+
+#[test]
+fn context_colors() {
+    use csscolorparser::{parse_with_context, ColorScheme, Context, SystemColor};
+
+    let red = Color::new(1.0, 0.0, 0.0, 1.0);
+    let light = Context::new(ColorScheme::Light).current_color(red.clone());
+    let dark = Context::new(ColorScheme::Dark)
+        .current_color(red)
+        .system_color(SystemColor::Canvas, Color::new(0.0, 0.0, 1.0, 1.0));
+
+    let test_data = [
+        ("currentcolor", [255, 0, 0, 255], [255, 0, 0, 255]),
+        ("CurrentColor", [255, 0, 0, 255], [255, 0, 0, 255]),
+        ("Canvas", [255, 255, 255, 255], [0, 0, 255, 255]),
+        ("canvastext", [0, 0, 0, 255], [255, 255, 255, 255]),
+        ("Window", [255, 255, 255, 255], [0, 0, 255, 255]),
+        ("light-dark(#0f0, #00f)", [0, 255, 0, 255], [0, 0, 255, 255]),
+        (
+            "LIGHT-DARK( #0f0 , currentcolor )",
+            [0, 255, 0, 255],
+            [255, 0, 0, 255],
+        ),
+        (
+            "light-dark(light-dark(#000, #fff), Canvas)",
+            [0, 0, 0, 255],
+            [0, 0, 255, 255],
+        ),
+        (
+            "rgb(from currentcolor r g 255)",
+            [255, 0, 255, 255],
+            [255, 0, 255, 255],
+        ),
+        (
+            "color-mix(in srgb, currentcolor, #00f)",
+            [128, 0, 128, 255],
+            [128, 0, 128, 255],
+        ),
+        (
+            "hsl(from light-dark(#f00, #0f0) h s l / 50%)",
+            [255, 0, 0, 128],
+            [0, 255, 0, 128],
+        ),
+    ];
+    for (s, l, d) in test_data {
+        assert_eq!(
+            parse_with_context(s, &light).unwrap().to_rgba8(),
+            l,
+            "{}",
+            s
+        );
+        assert_eq!(parse_with_context(s, &dark).unwrap().to_rgba8(), d, "{}", s);
+        assert_eq!(
+            Parser::strict()
+                .parse_with_context(s, &dark)
+                .unwrap()
+                .to_rgba8(),
+            d,
+            "{}",
+            s
+        );
+        assert_eq!(parse(s), Err(ParseColorError::NeedsContext), "{}", s);
+    }
+
+    // Context-free colors are not affected
+    assert_eq!(
+        parse_with_context("#f00", &dark).unwrap().to_rgba8(),
+        [255, 0, 0, 255]
+    );
+
+    let test_data = [
+        "currentcolor red",
+        "canvas()",
+        "buttonface(0)",
+        "light-dark()",
+        "light-dark(#fff)",
+        "light-dark(#fff #000)",
+        "light-dark(#fff, #000, #f00)",
+        "light-dark(#fff / #000)",
+        "light-dark(#fff, nope)",
+    ];
+    for s in test_data {
+        assert!(parse_with_context(s, &light).is_err(), "{}", s);
+        assert_ne!(parse(s), Err(ParseColorError::NeedsContext), "{}", s);
+    }
+
+    use ParseColorError::{InvalidLightDark, NeedsContext};
+
+    let args = Expected::Arguments {
+        count: "2 comma separated",
+        found: 1,
+    };
+    #[rustfmt::skip]
+    let test_data = [
+        ("currentcolor", NeedsContext, 0..12, "currentcolor", Expected::Context),
+        (" LinkText ", NeedsContext, 1..9, "LinkText", Expected::Context),
+        ("light-dark(#fff, #000)", NeedsContext, 0..10, "light-dark", Expected::Context),
+        ("rgb(from currentcolor r g b)", NeedsContext, 9..21, "currentcolor", Expected::Context),
+        ("light-dark(#fff #000)", InvalidLightDark, 16..20, "#000", Expected::Comma),
+        ("light-dark(#fff)", InvalidLightDark, 11..15, "#fff", args),
+    ];
+    for (s, kind, span, token, expected) in test_data {
+        let err = parse_detailed(s).unwrap_err();
+        assert_eq!(err.kind(), kind, "{}", s);
+        assert_eq!(err.span(), span, "{}", s);
+        assert_eq!(err.token(), token, "{}", s);
+        assert_eq!(err.expected(), expected, "{}", s);
+    }
+
+    let nested = |n| "light-dark(".repeat(n) + "#fff" + &", #000)".repeat(n);
+    assert!(parse_with_context(&nested(32), &light).is_ok());
+    assert_eq!(
+        parse_with_context(&nested(10_000), &light),
+        Err(InvalidLightDark)
+    );
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn vars() {
+    use csscolorparser::{parse_with_vars, VarError};
+    use std::collections::HashMap;
+
+    let vars = HashMap::from([
+        ("--r", "255"),
+        ("--g", "var(--r)"),
+        ("--rgb", "var(--r) var(--g) 0"),
+        ("--brand-500", "#0af"),
+        ("--alpha", "50%"),
+        ("--a", "var(--b, red)"),
+        ("--b", "var(--a, blue)"),
+        ("--self", "var(--self)"),
+        ("--missing", "var(--nope)"),
+    ]);
+
+    let test_data = [
+        ("#f00", [255, 0, 0, 255]),
+        ("var(--brand-500)", [0, 170, 255, 255]),
+        (" var( --brand-500 ) ", [0, 170, 255, 255]),
+        ("var(--brand-600, #0af)", [0, 170, 255, 255]),
+        ("var(--brand-600, var(--brand-500))", [0, 170, 255, 255]),
+        ("var(--brand-600, var(--brand-700, #f00))", [255, 0, 0, 255]),
+        ("rgb(var(--r) var(--g) 0)", [255, 255, 0, 255]),
+        ("rgb(var(--rgb) / var(--alpha))", [255, 255, 0, 128]),
+        ("rgb(var(--r), 0, 0)", [255, 0, 0, 255]),
+        ("rgb(calc(var(--r) / 5) 0 0)", [51, 0, 0, 255]),
+        (
+            "rgb(from var(--brand-500) r g b / var(--alpha))",
+            [0, 170, 255, 128],
+        ),
+        (
+            "color-mix(in srgb, var(--brand-500), var(--x, #000) 0%)",
+            [0, 170, 255, 255],
+        ),
+        // Custom properties in a cycle are invalid, their fallbacks aren't used
+        ("var(--a, #0f0)", [0, 255, 0, 255]),
+        ("var(--self, #0f0)", [0, 255, 0, 255]),
+        ("var(--missing, #0f0)", [0, 255, 0, 255]),
+    ];
+    for (s, rgba) in test_data {
+        assert_eq!(parse_with_vars(s, &vars).unwrap().to_rgba8(), rgba, "{}", s);
+    }
+
+    let test_data = [
+        (
+            "var(--brand-600)",
+            VarError::Undefined("--brand-600".into()),
+        ),
+        (
+            "rgb(var(--r) var(--x) 0)",
+            VarError::Undefined("--x".into()),
+        ),
+        ("var(--missing)", VarError::Undefined("--nope".into())),
+        (
+            "var(--BRAND-500)",
+            VarError::Undefined("--BRAND-500".into()),
+        ),
+        ("var(--self)", VarError::Cycle("--self".into())),
+        ("var(--a)", VarError::Cycle("--a".into())),
+        ("rgb(var(--b) 0 0)", VarError::Cycle("--b".into())),
+        ("var(brand)", VarError::InvalidVar),
+        ("var()", VarError::InvalidVar),
+        ("var(--r --g)", VarError::InvalidVar),
+        ("var(--r", VarError::InvalidVar),
+        (
+            "var(--alpha)",
+            VarError::Parse(ParseColorError::InvalidUnknown),
+        ),
+        (
+            "rgb(var(--r)%, 0, 0)",
+            VarError::Parse(ParseColorError::InvalidRgb),
+        ),
+    ];
+    for (s, err) in test_data {
+        assert_eq!(parse_with_vars(s, &vars).unwrap_err(), err, "{}", s);
+    }
+
+    // Closure resolver
+    let vars = |name: &str| {
+        name.strip_prefix("--gray-")
+            .map(|v| format!("rgb({0} {0} {0})", v))
+    };
+    let c = Parser::strict()
+        .parse_with_vars("var(--gray-51)", &vars)
+        .unwrap();
+    assert_eq!(c.to_rgba8(), [51, 51, 51, 255]);
+    assert!(Parser::strict()
+        .parse_with_vars("var(--x, ff0000)", &vars)
+        .is_err());
+
+    assert_eq!(
+        VarError::Undefined("--x".into()).to_string(),
+        "undefined variable --x"
+    );
+    assert_eq!(
+        VarError::Cycle("--x".into()).to_string(),
+        "cyclic reference in variable --x"
+    );
+    assert_eq!(
+        VarError::Parse(ParseColorError::InvalidRgb).to_string(),
+        "invalid rgb format"
+    );
+
+    // Each custom property doubles the length of the substitution
+    let mut vars: HashMap<String, String> = (0..40)
+        .map(|i| {
+            (
+                format!("--v{}", i),
+                format!("var(--v{0}) var(--v{0})", i + 1),
+            )
+        })
+        .collect();
+    vars.insert("--v40".into(), "0".into());
+    let err = parse_with_vars("rgb(var(--v0) 0 0)", &vars).unwrap_err();
+    assert_eq!(err, VarError::TooLong);
+    assert_eq!(err.to_string(), "substituted value is too long");
+    let err = parse_with_vars("rgb(var(--v0, 0) 0 0)", &vars).unwrap_err();
+    assert_eq!(err, VarError::TooLong);
+    assert_eq!(
+        parse_with_vars("rgb(var(--v38) 0 0)", &vars).unwrap_err(),
+        VarError::Parse(ParseColorError::InvalidRgb)
+    );
+
+    // Without growth, references to the same custom property in different
+    // places are fine
+    let vars = HashMap::from([("--z", "0")]);
+    let s = format!("rgb(calc(255{}) 0 0)", " + var(--z)".repeat(500));
+    assert_eq!(
+        parse_with_vars(&s, &vars).unwrap().to_rgba8(),
+        [255, 0, 0, 255]
+    );
+}
+
+#[test]
+fn custom_names() {
+    const ACME: csscolorparser::NameTable = &[
+        ("acme-blue", [0, 87, 183]),
+        ("acme-orange", [255, 120, 0]),
+        ("red", [200, 0, 0]),
+    ];
+    const OTHER: csscolorparser::NameTable =
+        &[("acme-blue", [0, 0, 255]), ("Other-Green", [0, 128, 0])];
+
+    let parser = Parser::new().names(&[ACME, OTHER]);
+
+    let test_data = [
+        ("acme-blue", [0, 87, 183, 255]),
+        ("ACME-Orange", [255, 120, 0, 255]),
+        (" acme\\-orange ", [255, 120, 0, 255]),
+        ("other-green", [0, 128, 0, 255]),
+        ("red", [200, 0, 0, 255]),
+        ("rgb(from acme-blue r g b / 50%)", [0, 87, 183, 128]),
+        ("color-mix(in srgb, acme-orange, #000 100%)", [0, 0, 0, 255]),
+        ("#0f0", [0, 255, 0, 255]),
+    ];
+    for (s, rgba) in test_data {
+        assert_eq!(parser.parse(s).unwrap().to_rgba8(), rgba, "{}", s);
+        let strict = Parser::strict().names(&[ACME, OTHER]);
+        assert_eq!(strict.parse(s).unwrap().to_rgba8(), rgba, "{}", s);
+    }
+
+    for s in ["acme-blue", "acme-orange", "acme-blue acme-orange", "acme"] {
+        assert!(parse(s).is_err(), "{}", s);
+    }
+    assert!(parser.parse("acme-blue acme-orange").is_err());
+    assert!(parser.parse("acme").is_err());
+
+    // Instead of the CSS names
+    let parser = Parser::new().names(&[ACME]).named_colors(false);
+    assert_eq!(parser.parse("red").unwrap().to_rgba8(), [200, 0, 0, 255]);
+    assert!(parser.parse("blue").is_err());
+    assert!(parser.parse("transparent").is_ok());
+
+    let parser = Parser::new().named_colors(false);
+    assert!(parser.parse("red").is_err());
+
+    // Color names
+    let parser = Parser::new().names(&[ACME, OTHER]);
+    let test_data = [
+        ([0, 87, 183], Some("acme-blue")),
+        ([0, 0, 255], Some("acme-blue")),
+        ([0, 128, 0], Some("Other-Green")),
+        ([200, 0, 0], Some("red")),
+        ([1, 2, 3], None),
+    ];
+    for ([r, g, b], name) in test_data {
+        assert_eq!(Color::from_rgba8(r, g, b, 255).name_in(&parser), name);
+        assert_eq!(Color::from_rgba8(r, g, b, 128).name_in(&parser), name);
+    }
+
+    #[cfg(feature = "named-colors")]
+    {
+        let c = Color::from_rgba8(255, 0, 0, 255);
+        assert_eq!(c.name_in(&parser), Some("red"));
+        assert_eq!(c.name_in(&Parser::new()), c.name());
+        assert_eq!(c.name_in(&parser.clone().named_colors(false)), None);
+    }
+}
+
+#[cfg(feature = "x11")]
+#[test]
+fn x11() {
+    let parser = Parser::new().x11(true);
+
+    let test_data = [
+        ("DarkSlateGray4", [82, 139, 139, 255]),
+        ("gray73", [186, 186, 186, 255]),
+        ("Grey100", [255, 255, 255, 255]),
+        ("light goldenrod", [238, 221, 130, 255]),
+        (" Navy Blue ", [0, 0, 128, 255]),
+        ("white smoke", [245, 245, 245, 255]),
+        ("aliceblue", [240, 248, 255, 255]),
+        ("rebeccapurple", [102, 51, 153, 255]),
+        ("transparent", [0, 0, 0, 0]),
+        // X11 and CSS disagree
+        ("gray", [190, 190, 190, 255]),
+        ("green", [0, 255, 0, 255]),
+        ("maroon", [176, 48, 96, 255]),
+        ("purple", [160, 32, 240, 255]),
+        ("web gray", [128, 128, 128, 255]),
+        ("WebGreen", [0, 128, 0, 255]),
+        ("x11 maroon", [176, 48, 96, 255]),
+        // XParseColor syntaxes
+        ("rgb:ff/80/00", [255, 128, 0, 255]),
+        ("RGB:f/8/0", [255, 136, 0, 255]),
+        ("rgb:ffff/8080/0", [255, 128, 0, 255]),
+        ("rgb:fff/0/800", [255, 0, 128, 255]),
+        ("rgbi:1/0.5/0", [255, 128, 0, 255]),
+        ("CIEXYZ:0.9505/1.0/1.089", [255, 255, 255, 255]),
+        ("CIEXYZ:0.4124/0.2126/0.0193", [255, 0, 0, 255]),
+        ("CIExyY:0.3127/0.3290/1", [255, 255, 255, 255]),
+        ("CIEuvY:0.1978/0.4683/1", [255, 255, 255, 255]),
+        ("CIELab:53.24/80.09/67.2", [255, 0, 0, 255]),
+        ("CIELuv:53.24/175.01/37.76", [255, 0, 0, 255]),
+        ("CIELab:0/0/0", [0, 0, 0, 255]),
+        ("CIELuv:0/0/0", [0, 0, 0, 255]),
+        ("#fff000000", [255, 0, 0, 255]),
+        ("#ffff80800000", [255, 128, 0, 255]),
+        // CSS hex colors
+        ("#f80", [255, 136, 0, 255]),
+        ("#ff8000", [255, 128, 0, 255]),
+    ];
+    for (s, rgba) in test_data {
+        assert_eq!(parser.parse(s).unwrap().to_rgba8(), rgba, "{}", s);
+    }
+
+    let test_data = [
+        "navy  blue",
+        "navy blue red",
+        "gray101",
+        "rgb:ff/80",
+        "rgb:ff/80/00/00",
+        "rgb:fffff/0/0",
+        "rgb:/0/0",
+        "rgb:gg/0/0",
+        "rgbi:1/0.5/2",
+        "rgbi:1/nan/0",
+        "cmyk:0/0/0",
+        "CIEXYZ:a/b/c",
+        "#fff0000000",
+        "#fff00000g",
+    ];
+    for s in test_data {
+        assert!(parser.parse(s).is_err(), "{}", s);
+    }
+
+    for s in ["DarkSlateGray4", "navy blue", "rgb:ff/80/00", "#fff000000"] {
+        assert!(parse(s).is_err(), "{}", s);
+    }
+
+    #[cfg(feature = "named-colors")]
+    for s in ["gray", "green", "maroon", "purple"] {
+        assert_ne!(parse(s), parser.parse(s), "{}", s);
+    }
+}
+
+#[test]
+fn html_legacy() {
+    use csscolorparser::parse_html_legacy;
+
+    let long = format!("#{}ff", "0".repeat(125));
+    let longer = format!("{}ff", "0".repeat(127));
+
+    let test_data = [
+        ("chucknorris", [192, 0, 0]),
+        ("ChuckNorris", [192, 0, 0]),
+        ("sick", [0, 192, 0]),
+        ("crap", [192, 160, 0]),
+        ("#fff", [255, 255, 255]),
+        ("#FFF", [255, 255, 255]),
+        ("fff", [15, 15, 15]),
+        ("#ff8000", [255, 128, 0]),
+        ("ff8000", [255, 128, 0]),
+        ("  #ff8000\n", [255, 128, 0]),
+        ("#ff800080", [255, 0, 128]),
+        ("#f80a", [248, 10, 0]),
+        ("#", [0, 0, 0]),
+        ("#12345", [18, 52, 80]),
+        ("#1", [1, 0, 0]),
+        ("#00000a00000b00000c", [10, 11, 12]),
+        ("#123456789abc123456789abc", [18, 154, 86]),
+        ("#123456789abc123456789abc123", [35, 188, 137]),
+        ("rgb(255, 0, 0)", [0, 85, 0]),
+        ("#ff#00#00", [255, 0, 0]),
+        ("\u{1F600}f", [0, 0, 15]),
+        ("f\u{1F600}", [15, 0, 0]),
+        ("é", [0, 0, 0]),
+        ("   ", [0, 0, 0]),
+        (" \t\n", [0, 0, 0]),
+        (&long, [0, 0, 255]),
+        (&longer, [0, 0, 240]),
+    ];
+    for (s, [r, g, b]) in test_data {
+        let c = parse_html_legacy(s).unwrap();
+        assert_eq!(c.to_rgba8(), [r, g, b, 255], "{}", s);
+    }
+
+    #[cfg(feature = "named-colors")]
+    {
+        let test_data = [
+            ("red", [255, 0, 0]),
+            (" RebeccaPurple ", [102, 51, 153]),
+            ("gray", [128, 128, 128]),
+            // Not a named color, read as hex
+            ("reed", [14, 237, 0]),
+        ];
+        for (s, [r, g, b]) in test_data {
+            let c = parse_html_legacy(s).unwrap();
+            assert_eq!(c.to_rgba8(), [r, g, b, 255], "{}", s);
+        }
+    }
+
+    for s in ["", "transparent", "TRANSPARENT", " transparent "] {
+        assert_eq!(
+            parse_html_legacy(s),
+            Err(ParseColorError::InvalidUnknown),
+            "{}",
+            s
+        );
+    }
+}
+
+#[test]
+fn dialects() {
+    use csscolorparser::{parse_dialect, Dialect};
+
+    let test_data = [
+        (Dialect::Css, "#ff000080", [255, 0, 0, 128]),
+        (Dialect::Css, "rgb(255 0 0 / 50%)", [255, 0, 0, 128]),
+        (Dialect::Android, "#80FF0000", [255, 0, 0, 128]),
+        (Dialect::Android, " #ff0000 ", [255, 0, 0, 255]),
+        (Dialect::Android, "#8F00", [255, 0, 0, 136]),
+        (Dialect::Android, "#F00", [255, 0, 0, 255]),
+        (Dialect::Flutter, "0xFF336699", [51, 102, 153, 255]),
+        (Dialect::Flutter, "Color(0x80336699)", [51, 102, 153, 128]),
+        (Dialect::Flutter, "Color (0x80336699)", [51, 102, 153, 128]),
+        (Dialect::Flutter, "0x336699", [51, 102, 153, 0]),
+        (
+            Dialect::Flutter,
+            "Color.fromARGB(128, 51, 102, 153)",
+            [51, 102, 153, 128],
+        ),
+        (
+            Dialect::Flutter,
+            "Color.fromRGBO(51, 102, 153, 0.5)",
+            [51, 102, 153, 128],
+        ),
+        (Dialect::Qt, "QColor(51, 102, 153)", [51, 102, 153, 255]),
+        (
+            Dialect::Qt,
+            "QColor(51, 102, 153, 128)",
+            [51, 102, 153, 128],
+        ),
+        (
+            Dialect::Qt,
+            "QColor::fromRgb(51,102,153)",
+            [51, 102, 153, 255],
+        ),
+        (
+            Dialect::Qt,
+            "QColor::fromRgbF(1, 0.5, 0, 0.5)",
+            [255, 128, 0, 128],
+        ),
+        (Dialect::Qt, "QColor(\"#80336699\")", [51, 102, 153, 128]),
+        (Dialect::Qt, "QColor(\"#336699\")", [51, 102, 153, 255]),
+        (Dialect::Qt, "QColor(\"#fff\")", [255, 255, 255, 255]),
+        (
+            Dialect::Qt,
+            "QColor(\"rgb(51 102 153)\")",
+            [51, 102, 153, 255],
+        ),
+        (
+            Dialect::DotNet,
+            "Color.FromArgb(128, 51, 102, 153)",
+            [51, 102, 153, 128],
+        ),
+        (
+            Dialect::DotNet,
+            "FromArgb(51, 102, 153)",
+            [51, 102, 153, 255],
+        ),
+        (
+            Dialect::UiKit,
+            "UIColor(red: 1, green: 0.5, blue: 0, alpha: 0.5)",
+            [255, 128, 0, 128],
+        ),
+        (
+            Dialect::UiKit,
+            "NSColor(red:1.0,green:0.5,blue:0.0,alpha:1.0)",
+            [255, 128, 0, 255],
+        ),
+    ];
+    for (dialect, s, rgba) in test_data {
+        let c = parse_dialect(s, dialect).unwrap();
+        assert_eq!(c.to_rgba8(), rgba, "{}", s);
+
+        // Round trip
+        let f = c.to_dialect_string(dialect);
+        assert_eq!(
+            parse_dialect(&f, dialect).unwrap().to_rgba8(),
+            rgba,
+            "{}",
+            f
+        );
+    }
+
+    let test_data = [
+        (Dialect::Android, "80FF0000"),
+        (Dialect::Android, "#80FF000"),
+        (Dialect::Android, "#80FF00GG"),
+        (Dialect::Android, "red"),
+        (Dialect::Flutter, "0x"),
+        (Dialect::Flutter, "0x1FF336699"),
+        (Dialect::Flutter, "0x+FF336699"),
+        (Dialect::Flutter, "Color(0x+F336699)"),
+        (Dialect::Flutter, "#FF336699"),
+        (Dialect::Flutter, "Color(0xFF336699"),
+        (Dialect::Flutter, "Color.fromARGB(256, 0, 0, 0)"),
+        (Dialect::Flutter, "Color.fromARGB(0, 0, 0)"),
+        (Dialect::Flutter, "Color.fromRGBO(0, 0, 0, 2)"),
+        (Dialect::Qt, "QColor(0, 0)"),
+        (Dialect::Qt, "QColor(0, 0, 0, 0, 0)"),
+        (Dialect::Qt, "QColor(#fff)"),
+        (Dialect::Qt, "QColor(\"#8fff\")"),
+        (Dialect::Qt, "QColor::fromRgbF(2, 0, 0)"),
+        (Dialect::DotNet, "Color.FromArgb(0, 0)"),
+        (Dialect::DotNet, "Color.FromArgb(+255, 0, 0, 0)"),
+        (Dialect::DotNet, "Color.FromRgb(0, 0, 0)"),
+        (Dialect::UiKit, "UIColor(red: 1, green: 0.5, blue: 0)"),
+        (Dialect::UiKit, "UIColor(1, 0.5, 0, 1)"),
+        (
+            Dialect::UiKit,
+            "UIColor(green: 1, red: 0.5, blue: 0, alpha: 1)",
+        ),
+    ];
+    for (dialect, s) in test_data {
+        assert!(parse_dialect(s, dialect).is_err(), "{}", s);
+    }
+
+    let c = Color::from_rgba8(51, 102, 153, 128);
+    let test_data = [
+        (Dialect::Css, "#33669980"),
+        (Dialect::Android, "#80336699"),
+        (Dialect::Flutter, "Color(0x80336699)"),
+        (Dialect::Qt, "QColor(51, 102, 153, 128)"),
+        (Dialect::DotNet, "Color.FromArgb(128, 51, 102, 153)"),
+        (
+            Dialect::UiKit,
+            "UIColor(red: 0.2, green: 0.4, blue: 0.6, alpha: 0.502)",
+        ),
+    ];
+    for (dialect, s) in test_data {
+        assert_eq!(c.to_dialect_string(dialect), s);
+    }
+    assert_eq!(
+        Color::new(1.0, 0.0, 0.0, 1.0).to_dialect_string(Dialect::Android),
+        "#FF0000"
+    );
+}
+
+#[test]
+fn preserved() {
+    use csscolorparser::{ColorSpace, Notation, ParsedColor};
+
+    let test_data = [
+        (
+            "oklch(0.9 0.4 150)",
+            "oklch(0.9 0.4 150)",
+            Notation::Function,
+        ),
+        (
+            "OKLCH(90% 100% 150deg)",
+            "oklch(0.9 0.4 150)",
+            Notation::Function,
+        ),
+        ("rgb(300 0 0)", "rgb(300 0 0)", Notation::Function),
+        (
+            "rgba(255, 0, 0, 0.5)",
+            "rgb(255 0 0 / 0.5)",
+            Notation::Function,
+        ),
+        (
+            "rgb(none 0 0 / none)",
+            "rgb(none 0 0 / none)",
+            Notation::Function,
+        ),
+        (
+            "hsl(120deg 50% none)",
+            "hsl(120 50% none)",
+            Notation::Function,
+        ),
+        (
+            "hwb(90 10% 20% / 30%)",
+            "hwb(90 10% 20% / 0.3)",
+            Notation::Function,
+        ),
+        ("hsv(0, 100%, 50%)", "hsv(0 100% 50%)", Notation::Function),
+        (
+            "rgb(12.345678 0.001 -0.5)",
+            "rgb(12.345678 0.001 -0.5)",
+            Notation::Function,
+        ),
+        (
+            "hsl(0.1 33.333% 0.5% / 0.123)",
+            "hsl(0.1 33.333% 0.5% / 0.123)",
+            Notation::Function,
+        ),
+        (
+            "oklab(0.123456789 -0.0001 0.3)",
+            "oklab(0.12345679 -0.0001 0.3)",
+            Notation::Function,
+        ),
+        (
+            "oklab(0.5 -0.1 0.1)",
+            "oklab(0.5 -0.1 0.1)",
+            Notation::Function,
+        ),
+        (
+            "color(display-p3 1 0.5 none)",
+            "color(display-p3 1 0.5 none)",
+            Notation::ColorFunction,
+        ),
+        (
+            "color(xyz 0.2 0.3 0.4 / 2)",
+            "color(xyz-d65 0.2 0.3 0.4 / 2)",
+            Notation::ColorFunction,
+        ),
+        (
+            "color-mix(in srgb, #f00, #00f)",
+            "color(srgb 0.5 0 0.5)",
+            Notation::ColorFunction,
+        ),
+        (
+            "color-mix(in hsl, #f00, #00f)",
+            "hsl(300 100% 50%)",
+            Notation::Function,
+        ),
+        ("rgb(from #f00 r g b)", "rgb(255 0 0)", Notation::Function),
+        ("#f008", "#ff000088", Notation::Hex),
+        ("ff8000", "#ff8000", Notation::Hex),
+        ("transparent", "transparent", Notation::Named),
+    ];
+    #[cfg(feature = "named-colors")]
+    let test_data = [
+        &test_data[..],
+        &[
+            ("red", "red", Notation::Named),
+            ("RebeccaPurple", "rebeccapurple", Notation::Named),
+        ],
+    ]
+    .concat();
+    for &(s, serialized, notation) in &test_data {
+        let c = ParsedColor::parse(s).unwrap();
+        assert_eq!(c.to_string(), serialized, "{}", s);
+        assert_eq!(c.notation(), notation, "{}", s);
+        assert_eq!(c.to_color(), parse(s).unwrap(), "{}", s);
+
+        // Round trip
+        let c2: ParsedColor = serialized.parse().unwrap();
+        assert_eq!(c2.to_string(), serialized, "{}", s);
+        assert_eq!(c2.components(), c.components(), "{}", s);
+    }
+
+    let c = ParsedColor::parse("rgb(300 -20 0 / 150%)").unwrap();
+    assert_eq!(c.space(), ColorSpace::Srgb);
+    assert_eq!(
+        c.components(),
+        [Some(300.0 / 255.0), Some(-20.0 / 255.0), Some(0.0)]
+    );
+    assert_eq!(c.alpha(), Some(1.5));
+    assert_eq!(
+        c.to_color().to_array(),
+        [300.0 / 255.0, -20.0 / 255.0, 0.0, 1.0]
+    );
+
+    let c = ParsedColor::new(
+        ColorSpace::Oklch,
+        [Some(0.7), None, Some(30.0)],
+        Some(0.5),
+        Notation::Function,
+    );
+    assert_eq!(c.to_string(), "oklch(0.7 none 30 / 0.5)");
+    assert_eq!(
+        c,
+        ParsedColor::parse("oklch(70% none 30deg / 50%)").unwrap()
+    );
+
+    // Named colors without a keyword are written as hex
+    let c = ParsedColor::new(
+        ColorSpace::Srgb,
+        [Some(1.0), Some(0.0), Some(0.0)],
+        Some(1.0),
+        Notation::Named,
+    );
+    assert_eq!(c.name(), None);
+    assert_eq!(c.to_string(), "#ff0000");
+
+    let parser = Parser::strict();
+    assert!(parser.parse_preserved("ff0000").is_err());
+
+    #[cfg(feature = "named-colors")]
+    {
+        assert_ne!(c, ParsedColor::parse("red").unwrap());
+        assert_eq!(
+            parser.parse_preserved("red").unwrap().notation(),
+            Notation::Named
+        );
+    }
+}
+
+#[test]
+fn extended_range() {
+    let test_data = [
+        ("rgb(300 0 0)", [300.0 / 255.0, 0.0, 0.0, 1.0]),
+        ("rgb(200% -10% 50%)", [2.0, -0.1, 0.5, 1.0]),
+        ("rgb(0 0 0 / 150%)", [0.0, 0.0, 0.0, 1.0]),
+        ("hsl(0 100% 50% / -1)", [1.0, 0.0, 0.0, 0.0]),
+        ("hsl(0 150% 50%)", [1.25, -0.25, -0.25, 1.0]),
+        ("hsl(0 -50% 50%)", [0.5, 0.5, 0.5, 1.0]),
+        ("hsl(0, 150%, 50%)", [1.0, 0.0, 0.0, 1.0]),
+        ("color(srgb 1.5 -0.5 0)", [1.5, -0.5, 0.0, 1.0]),
+    ];
+    for (s, rgba) in test_data {
+        let c = parse(s).unwrap();
+        assert_eq!(c.to_array(), rgba, "{}", s);
+    }
+
+    let test_data = [
+        "oklch(0.8 0.35 30)",
+        "oklab(0.5 0.4 0)",
+        "color(display-p3 1 0 0)",
+        "color(rec2020 0 1 0)",
+        "color(xyz 0.1 0.9 0.1)",
+    ];
+    for s in test_data {
+        assert!(!parse(s).unwrap().is_in_gamut(), "{}", s);
+    }
+}